@@ -1,10 +1,12 @@
 use criterion::criterion_group;
 use criterion::criterion_main;
+use criterion::BenchmarkId;
 use criterion::Criterion;
 
 use fluent_langneg::convert_vec_str_to_langids_lossy;
 use fluent_langneg::negotiate_languages;
 use fluent_langneg::LanguageIdentifier;
+use fluent_langneg::NegotiationStrategy;
 
 #[no_mangle]
 #[inline(never)]
@@ -35,5 +37,127 @@ fn negotiate_bench(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, negotiate_bench);
+fn negotiate_exact_bench(c: &mut Criterion) {
+    let requested = &["sr-Cyrl", "zh-Hant", "en-GB", "de-DE"];
+    let available = &[
+        "en-US", "fr", "de", "en-GB", "it", "pl", "ru", "sr-Cyrl", "sr-Latn", "zh-Hant", "zh-Hans",
+        "ja-JP", "he-IL", "de-DE", "de-IT",
+    ];
+
+    let requested = convert_vec_str_to_langids_lossy(requested);
+    let available = convert_vec_str_to_langids_lossy(available);
+
+    c.bench_function("negotiate_exact", |b| {
+        b.iter(|| do_negotiate(&requested, &available))
+    });
+}
+
+fn negotiate_strategies_bench(c: &mut Criterion) {
+    let small = &[
+        "en-US", "fr", "de", "en-GB", "it", "pl", "ru", "sr-Cyrl", "sr-Latn", "zh-Hant", "zh-Hans",
+        "ja-JP", "he-IL", "de-DE", "de-IT",
+    ];
+    let languages = &[
+        "af", "ar", "az", "be", "bg", "bn", "ca", "cs", "cy", "da", "de", "el", "en", "es", "et",
+        "eu", "fa", "fi", "fr", "ga", "gl", "gu", "he", "hi", "hr", "hu", "hy", "id", "is", "it",
+        "ja", "ka", "kk", "km", "kn", "ko", "lt", "lv", "mk", "ml", "mn", "mr", "ms", "my", "nb",
+        "ne", "nl", "pa", "pl", "pt", "ro", "ru", "si", "sk", "sl", "sq", "sr", "sv", "sw", "ta",
+        "te", "th", "tr", "uk", "ur", "uz", "vi", "zh",
+    ];
+    let regions = &["", "-BR", "-CA", "-DE", "-GB", "-IN", "-US"];
+    let large: Vec<String> = languages
+        .iter()
+        .flat_map(|language| {
+            regions
+                .iter()
+                .map(move |region| format!("{language}{region}"))
+        })
+        .collect();
+
+    let exact = &["sr-Cyrl", "zh-Hant", "en-GB", "de-DE"];
+    let fuzzy = &["de-AT", "it-CH", "ru-UA", "en-AU"];
+    let unsupported = &["gd", "ga-IE", "cy", "en-IE"];
+
+    let available = [
+        ("small", convert_vec_str_to_langids_lossy(small)),
+        ("large", convert_vec_str_to_langids_lossy(&large)),
+    ];
+    let requested = [
+        ("exact", convert_vec_str_to_langids_lossy(exact)),
+        ("fuzzy", convert_vec_str_to_langids_lossy(fuzzy)),
+        ("unsupported", convert_vec_str_to_langids_lossy(unsupported)),
+    ];
+    let strategies = [
+        ("filtering", NegotiationStrategy::Filtering),
+        ("matching", NegotiationStrategy::Matching),
+        ("lookup", NegotiationStrategy::Lookup),
+    ];
+
+    let mut group = c.benchmark_group("negotiate_strategies");
+    for (available_name, available) in &available {
+        for (requested_name, requested) in &requested {
+            for (strategy_name, strategy) in strategies {
+                let id = format!("{available_name}/{requested_name}/{strategy_name}");
+                group.bench_with_input(BenchmarkId::from_parameter(id), &strategy, |b, &s| {
+                    b.iter(|| negotiate_languages(requested, available, None, s))
+                });
+            }
+        }
+    }
+    group.finish();
+}
+
+fn negotiate_mixed_languages_bench(c: &mut Criterion) {
+    // Only the last available locale shares the language of the requested one,
+    // so nearly every comparison fails on the language subtag.
+    let requested = &["pt-Latn-PT-fonipa"];
+    let mut available: Vec<String> = [
+        "af", "ar", "az", "be", "bg", "bn", "ca", "cs", "cy", "da", "de", "el", "en", "es", "et",
+        "eu", "fa", "fi", "fr", "ga", "gl", "gu", "he", "hi", "hr", "hu", "hy", "id", "is", "it",
+    ]
+    .iter()
+    .flat_map(|language| {
+        ["-Latn-BR-fonipa", "-Cyrl-PT", "-Arab-IN", "-Latn-US-1901"]
+            .iter()
+            .map(move |rest| format!("{language}{rest}"))
+    })
+    .collect();
+    available.push("pt-Latn-PT-fonipa".to_string());
+
+    let requested = convert_vec_str_to_langids_lossy(requested);
+    let available = convert_vec_str_to_langids_lossy(&available);
+
+    c.bench_function("negotiate_mixed_languages", |b| {
+        b.iter(|| do_negotiate(&requested, &available))
+    });
+}
+
+fn negotiate_language_ranges_bench(c: &mut Criterion) {
+    // Available locales are mostly bare languages, which are matched as ranges
+    // from step 2 onwards.
+    let requested = &["de-AT", "sr-Latn-RS", "pt-BR", "zh-Hant-TW"];
+    let available = &[
+        "af", "ar", "az", "be", "bg", "bn", "ca", "cs", "cy", "da", "de", "el", "en", "es", "et",
+        "eu", "fa", "fi", "fr", "ga", "gl", "gu", "he", "hi", "hr", "hu", "hy", "id", "is", "it",
+        "ja", "ka", "kk", "km", "kn", "ko", "lt", "lv", "mk", "ml", "mn", "mr", "ms", "my", "nb",
+        "ne", "nl", "pa", "pl", "pt", "ro", "ru", "si", "sk", "sl", "sq", "sr", "sv", "sw", "ta",
+        "te", "th", "tr", "uk", "ur", "uz", "vi", "zh",
+    ];
+
+    let requested = convert_vec_str_to_langids_lossy(requested);
+    let available = convert_vec_str_to_langids_lossy(available);
+
+    c.bench_function("negotiate_language_ranges", |b| {
+        b.iter(|| do_negotiate(&requested, &available))
+    });
+}
+
+criterion_group!(
+    benches,
+    negotiate_bench,
+    negotiate_exact_bench,
+    negotiate_strategies_bench,
+    negotiate_mixed_languages_bench,
+    negotiate_language_ranges_bench
+);
 criterion_main!(benches);
@@ -29,13 +29,52 @@
 //! This function ignores the weights associated with the locales, since Fluent Locale
 //! language negotiation only uses the order of locales, not the weights.
 //!
+//! Lists of strings produced by other Accept-Language parsers, like the
+//! `accept-language` crate's `Vec<String>`, can be passed directly to
+//! [`convert_vec_str_to_langids_lossy`](crate::convert_vec_str_to_langids_lossy).
+//!
 
 use icu_locid::LanguageIdentifier;
 
+use crate::langid::{parse_tag, trim_tag};
+use crate::negotiate::{negotiate_languages, NegotiationStrategy};
+
+#[must_use]
 pub fn parse(s: &str) -> Vec<LanguageIdentifier> {
     s.split(',')
-        .map(|t| t.trim().split(';').next().unwrap())
+        .map(|t| trim_tag(t.split(';').next().unwrap().as_bytes()))
         .filter(|t| !t.is_empty())
-        .filter_map(|t| t.parse().ok())
+        .filter_map(|t| parse_tag(t).ok())
         .collect()
 }
+
+/// Parses an Accept-Language header and negotiates it against `available`.
+///
+/// This is a shorthand for [`parse`] followed by
+/// [`negotiate_languages`](crate::negotiate_languages).
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::{convert_vec_str_to_langids_lossy, negotiate_from_accept_language};
+/// use fluent_langneg::NegotiationStrategy;
+///
+/// let available = convert_vec_str_to_langids_lossy(&["en-US", "fr", "de"]);
+///
+/// let supported = negotiate_from_accept_language(
+///     "fr-CA,fr;q=0.8,en;q=0.5",
+///     &available,
+///     Some(&available[0]),
+///     NegotiationStrategy::Lookup,
+/// );
+/// assert_eq!(supported, vec![&available[1]]);
+/// ```
+#[must_use]
+pub fn negotiate_from_accept_language<'a, A: 'a + AsRef<LanguageIdentifier> + PartialEq>(
+    header: &str,
+    available: &'a [A],
+    default: Option<&'a A>,
+    strategy: NegotiationStrategy,
+) -> Vec<&'a A> {
+    negotiate_languages(&parse(header), available, default, strategy)
+}
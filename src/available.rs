@@ -0,0 +1,139 @@
+//! Available locales parsed ahead of time, like at build time, and loaded
+//! from a compact binary encoding at startup.
+//!
+//! Only parsing is done ahead of time. The locales are stored as parsed, not
+//! maximized, since the negotiation adds likely subtags to the requested
+//! locales only, and matching against maximized available locales would
+//! change its results.
+//!
+//! # Example:
+//!
+//! ```
+//! use fluent_langneg::available::AvailableSet;
+//! use fluent_langneg::{convert_vec_str_to_langids_lossy, negotiate_languages, NegotiationStrategy};
+//! use icu_locid::langid;
+//!
+//! // At build time.
+//! let bytes = AvailableSet::precompute(&["en-US", "de", "sr_Latn"]).to_bytes();
+//!
+//! // At startup.
+//! let available = AvailableSet::from_bytes(&bytes).expect("Invalid available set.");
+//! let requested = convert_vec_str_to_langids_lossy(&["sr-Latn-RS"]);
+//! assert_eq!(
+//!     negotiate_languages(&requested, &available, None, NegotiationStrategy::Lookup),
+//!     &[&langid!("sr-Latn")]
+//! );
+//! ```
+
+use std::ops::Deref;
+
+use icu_locid::{
+    subtags::{Language, Region, Script, Variant, Variants},
+    LanguageIdentifier, ParserError,
+};
+
+use crate::available_from_tags;
+
+/// The version of the encoding, stored as the first byte.
+const FORMAT_VERSION: u8 = 1;
+
+/// A list of unique available locales, which can be encoded with
+/// [`AvailableSet::to_bytes`] and decoded without parsing tags.
+///
+/// It dereferences to a slice of [`LanguageIdentifier`]s, so it can be passed
+/// to the negotiation functions and to a [`Negotiator`](crate::Negotiator) as
+/// the available locales.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AvailableSet {
+    locales: Vec<LanguageIdentifier>,
+}
+
+impl AvailableSet {
+    /// Parses the tags like [`available_from_tags`], skipping the ones which
+    /// fail to parse.
+    ///
+    /// No likely subtags are added, so the locales are negotiated like the
+    /// tags themselves.
+    #[must_use]
+    pub fn precompute(tags: &[&str]) -> Self {
+        let (locales, _) = available_from_tags(tags);
+        Self { locales }
+    }
+
+    /// Encodes the locales.
+    ///
+    /// Each locale is stored as its subtags in their fixed-size raw form, with
+    /// absent subtags zeroed and the variants terminated by a zeroed variant.
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![FORMAT_VERSION];
+        for langid in &self.locales {
+            bytes.extend(langid.language.into_raw());
+            bytes.extend(langid.script.map_or([0; 4], Script::into_raw));
+            bytes.extend(langid.region.map_or([0; 3], Region::into_raw));
+            for variant in langid.variants.iter() {
+                bytes.extend(variant.into_raw());
+            }
+            bytes.extend([0; 8]);
+        }
+        bytes
+    }
+
+    /// Decodes locales encoded with [`AvailableSet::to_bytes`].
+    ///
+    /// Returns an error if the bytes are truncated, have been encoded with
+    /// another version of the encoding, or contain invalid subtags.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ParserError> {
+        let Some((&FORMAT_VERSION, mut bytes)) = bytes.split_first() else {
+            return Err(ParserError::InvalidSubtag);
+        };
+
+        let mut locales = vec![];
+        while !bytes.is_empty() {
+            let language = Language::try_from_raw(take(&mut bytes)?)?;
+            let script = match take(&mut bytes)? {
+                [0, 0, 0, 0] => None,
+                raw => Some(Script::try_from_raw(raw)?),
+            };
+            let region = match take(&mut bytes)? {
+                [0, 0, 0] => None,
+                raw => Some(Region::try_from_raw(raw)?),
+            };
+            let mut variants: Vec<Variant> = vec![];
+            loop {
+                match take(&mut bytes)? {
+                    [0, 0, 0, 0, 0, 0, 0, 0] => break,
+                    raw => variants.push(Variant::try_from_raw(raw)?),
+                }
+            }
+            // Variants are sorted and unique in a parsed identifier.
+            if variants.windows(2).any(|v| v[0] >= v[1]) {
+                return Err(ParserError::InvalidSubtag);
+            }
+
+            locales.push(LanguageIdentifier {
+                language,
+                script,
+                region,
+                variants: Variants::from_vec_unchecked(variants),
+            });
+        }
+        Ok(Self { locales })
+    }
+}
+
+impl Deref for AvailableSet {
+    type Target = [LanguageIdentifier];
+
+    fn deref(&self) -> &Self::Target {
+        &self.locales
+    }
+}
+
+fn take<const N: usize>(bytes: &mut &[u8]) -> Result<[u8; N], ParserError> {
+    let (raw, rest) = bytes
+        .split_first_chunk::<N>()
+        .ok_or(ParserError::InvalidSubtag)?;
+    *bytes = rest;
+    Ok(*raw)
+}
@@ -0,0 +1,145 @@
+//! Reads the requested locales from the POSIX locale environment variables,
+//! following the resolution order of gettext, so command-line tools can
+//! negotiate them like the rest of the system.
+//!
+//! The locale of messages is taken from the first non-empty of `LC_ALL`,
+//! `LC_MESSAGES` and `LANG`. Unless it is `C` or `POSIX`, the colon-separated
+//! list in `LANGUAGE` takes precedence over it, and the locale itself is kept
+//! as the last requested one.
+//!
+//! POSIX locale names like `de_AT.UTF-8@euro` are normalized to language
+//! identifiers by dropping their codeset and modifier, except for the `latin`
+//! and `cyrillic` modifiers, which are replaced with the script they name.
+//!
+//! In the other direction, [`to_gettext_language_chain`](crate::to_gettext_language_chain)
+//! formats negotiated locales as a value of `LANGUAGE`, for passing them on to
+//! gettext.
+//!
+//! # Example:
+//!
+//! ```
+//! use fluent_langneg::env::requested_from;
+//! use icu_locid::langid;
+//!
+//! let requested = requested_from(|name| match name {
+//!     "LANG" => Some("sr_RS.UTF-8@latin".to_string()),
+//!     "LANGUAGE" => Some("de_AT:de".to_string()),
+//!     _ => None,
+//! });
+//! assert_eq!(
+//!     requested,
+//!     &[langid!("de-AT"), langid!("de"), langid!("sr-Latn-RS")]
+//! );
+//! ```
+
+use icu_locid::{subtags::script, LanguageIdentifier};
+
+use crate::langid::{parse_tag, trim_tag};
+use crate::negotiate::{negotiate_languages, NegotiationStrategy};
+
+/// Parses a POSIX locale name, like `en_US.UTF-8`, into a language identifier.
+///
+/// Returns `None` for the `C` and `POSIX` locales, and for names which fail to parse.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::env::parse_posix_locale;
+/// use icu_locid::langid;
+///
+/// assert_eq!(parse_posix_locale("en_US.UTF-8"), Some(langid!("en-US")));
+/// assert_eq!(parse_posix_locale("de_DE@euro"), Some(langid!("de-DE")));
+/// assert_eq!(parse_posix_locale("uz_UZ@cyrillic"), Some(langid!("uz-Cyrl-UZ")));
+/// assert_eq!(parse_posix_locale("C.UTF-8"), None);
+/// ```
+#[must_use]
+pub fn parse_posix_locale(name: &str) -> Option<LanguageIdentifier> {
+    let (name, modifier) = match name.split_once('@') {
+        Some((name, modifier)) => (name, Some(modifier)),
+        None => (name, None),
+    };
+    let name = name.split('.').next().unwrap_or_default();
+    if is_c_locale(name) {
+        return None;
+    }
+
+    let mut langid = parse_tag(trim_tag(name.as_bytes())).ok()?;
+    match modifier {
+        Some("latin") => langid.script = Some(script!("Latn")),
+        Some("cyrillic") => langid.script = Some(script!("Cyrl")),
+        _ => {}
+    }
+    Some(langid)
+}
+
+fn is_c_locale(name: &str) -> bool {
+    matches!(name.trim(), "C" | "POSIX")
+}
+
+/// Returns the requested locales, reading the environment variables with `var`.
+///
+/// This allows resolving the locales from a source other than the process
+/// environment. See the [module documentation](self) for the resolution order.
+#[must_use]
+pub fn requested_from<F>(var: F) -> Vec<LanguageIdentifier>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let Some(locale) = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| var(name))
+        .find(|value| !value.is_empty())
+    else {
+        return vec![];
+    };
+    if is_c_locale(locale.split(['.', '@']).next().unwrap_or_default()) {
+        return vec![];
+    }
+
+    let mut requested: Vec<LanguageIdentifier> = vec![];
+    let language = var("LANGUAGE").unwrap_or_default();
+    for langid in language
+        .split(':')
+        .chain(Some(locale.as_str()))
+        .filter_map(parse_posix_locale)
+    {
+        if !requested.contains(&langid) {
+            requested.push(langid);
+        }
+    }
+    requested
+}
+
+/// Returns the requested locales from the environment of the process.
+///
+/// Variables which are not valid unicode are treated as unset.
+#[must_use]
+pub fn requested() -> Vec<LanguageIdentifier> {
+    requested_from(|name| std::env::var(name).ok())
+}
+
+/// Negotiates the locales requested by the environment of the process
+/// against `available`.
+///
+/// This is a shorthand for [`requested`] followed by
+/// [`negotiate_languages`](crate::negotiate_languages).
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::{convert_vec_str_to_langids_lossy, negotiate_from_env};
+/// use fluent_langneg::NegotiationStrategy;
+///
+/// let available = convert_vec_str_to_langids_lossy(&["en-US", "fr", "de"]);
+///
+/// let supported = negotiate_from_env(&available, Some(&available[0]), NegotiationStrategy::Lookup);
+/// assert_eq!(supported.len(), 1);
+/// ```
+#[must_use]
+pub fn negotiate_from_env<'a, A: 'a + AsRef<LanguageIdentifier> + PartialEq>(
+    available: &'a [A],
+    default: Option<&'a A>,
+    strategy: NegotiationStrategy,
+) -> Vec<&'a A> {
+    negotiate_languages(&requested(), available, default, strategy)
+}
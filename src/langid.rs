@@ -0,0 +1,871 @@
+//! Helpers for operating on language identifiers and the language tags
+//! they are parsed from.
+
+use std::fmt::Display;
+
+use icu_locid::{
+    langid,
+    subtags::{language, script, Language, Region, Script, Variant, Variants},
+    LanguageIdentifier, Locale, ParserError,
+};
+
+use crate::negotiate::{region_aliases, LocaleExpander, TransformResult};
+
+const SEPARATORS: &[char] = &['-', '_'];
+
+/// Strips surrounding whitespace and trailing separators from a tag.
+pub(crate) fn trim_tag(tag: &[u8]) -> &[u8] {
+    let start = tag
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(tag.len());
+    let end = tag
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace() && !SEPARATORS.contains(&char::from(*b)))
+        .map_or(start, |idx| idx + 1);
+    &tag[start..end.max(start)]
+}
+
+/// Returns an iterator over the progressively shorter tags an
+/// [RFC4647](https://www.ietf.org/rfc/rfc4647.txt) Lookup would try,
+/// starting with the tag itself.
+///
+/// When a subtag is removed, any single-character subtag (singleton) left
+/// at the end is removed as well.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::truncations;
+///
+/// assert_eq!(
+///     truncations("en-Latn-US-x-foo").collect::<Vec<_>>(),
+///     &["en-Latn-US-x-foo", "en-Latn-US", "en-Latn", "en"]
+/// );
+/// ```
+#[must_use]
+pub fn truncations(tag: &str) -> Truncations<'_> {
+    Truncations {
+        remaining: Some(tag).filter(|t| !t.is_empty()),
+    }
+}
+
+/// An iterator returned by [`truncations`].
+#[derive(Debug, Clone)]
+pub struct Truncations<'a> {
+    remaining: Option<&'a str>,
+}
+
+impl<'a> Iterator for Truncations<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.remaining?;
+        self.remaining = truncate(current);
+        Some(current)
+    }
+}
+
+fn truncate(tag: &str) -> Option<&str> {
+    let mut head = &tag[..tag.rfind(SEPARATORS)?];
+    loop {
+        match head.rfind(SEPARATORS) {
+            Some(idx) if head.len() - idx == 2 => head = &head[..idx],
+            Some(_) => return Some(head),
+            None if head.len() > 1 => return Some(head),
+            None => return None,
+        }
+    }
+}
+
+/// The subtags added by [`maximize`].
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum MaximizeDepth {
+    /// Add only the likely script, like `en` → `en-Latn`.
+    Script,
+    /// Add only the likely region, like `en` → `en-US`.
+    Region,
+    /// Add all likely subtags, like `en` → `en-Latn-US`.
+    Full,
+}
+
+/// Adds likely subtags to the identifier, limited to the ones selected by `depth`.
+///
+/// Subtags which are already present are kept. If there is no likely subtags
+/// data for the identifier, it is returned unchanged.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::{maximize, MaximizeDepth};
+/// use icu_locid::langid;
+///
+/// assert_eq!(maximize(&langid!("en"), MaximizeDepth::Script), langid!("en-Latn"));
+/// assert_eq!(maximize(&langid!("en"), MaximizeDepth::Region), langid!("en-US"));
+/// assert_eq!(maximize(&langid!("en"), MaximizeDepth::Full), langid!("en-Latn-US"));
+/// ```
+#[must_use]
+pub fn maximize(langid: &LanguageIdentifier, depth: MaximizeDepth) -> LanguageIdentifier {
+    let mut maximized = langid.clone();
+    if !add_likely_subtags(&mut maximized) {
+        return maximized;
+    }
+
+    match depth {
+        MaximizeDepth::Script => LanguageIdentifier {
+            script: maximized.script,
+            ..langid.clone()
+        },
+        MaximizeDepth::Region => LanguageIdentifier {
+            region: maximized.region,
+            ..langid.clone()
+        },
+        MaximizeDepth::Full => maximized,
+    }
+}
+
+/// Adds likely subtags to the identifier in place, like `en` → `en-Latn-US`,
+/// returning whether it has been modified.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::add_likely_subtags;
+/// use icu_locid::langid;
+///
+/// let mut langid = langid!("en");
+/// assert!(add_likely_subtags(&mut langid));
+/// assert_eq!(langid, langid!("en-Latn-US"));
+/// ```
+pub fn add_likely_subtags(langid: &mut LanguageIdentifier) -> bool {
+    LocaleExpander::new().maximize(langid) == TransformResult::Modified
+}
+
+/// Removes subtags which would be added back by [`add_likely_subtags`], like
+/// `en-Latn-US` → `en`, returning whether the identifier has been modified.
+///
+/// The shortest form is searched for in the order of the language alone,
+/// then with the region, and then with the script.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::remove_likely_subtags;
+/// use icu_locid::langid;
+///
+/// let mut langid = langid!("en-Latn-US");
+/// assert!(remove_likely_subtags(&mut langid));
+/// assert_eq!(langid, langid!("en"));
+///
+/// let mut langid = langid!("en-GB");
+/// assert!(!remove_likely_subtags(&mut langid));
+/// ```
+pub fn remove_likely_subtags(langid: &mut LanguageIdentifier) -> bool {
+    // Likely subtags only fill in missing subtags, so the ones present are
+    // kept even if the data would replace them.
+    let maximize = |langid: &mut LanguageIdentifier| {
+        let (script, region) = (langid.script, langid.region);
+        add_likely_subtags(langid);
+        langid.script = script.or(langid.script);
+        langid.region = region.or(langid.region);
+    };
+
+    let mut maximized = LanguageIdentifier {
+        variants: Variants::new(),
+        ..langid.clone()
+    };
+    maximize(&mut maximized);
+
+    let candidates = [
+        (None, None),
+        (None, maximized.region),
+        (maximized.script, None),
+    ];
+    for (script, region) in candidates {
+        let mut candidate = LanguageIdentifier {
+            language: maximized.language,
+            script,
+            region,
+            variants: Variants::new(),
+        };
+        maximize(&mut candidate);
+        if candidate == maximized {
+            let modified = langid.language != maximized.language
+                || langid.script != script
+                || langid.region != region;
+            langid.language = maximized.language;
+            langid.script = script;
+            langid.region = region;
+            return modified;
+        }
+    }
+    false
+}
+
+/// Returns the script of the identifier, or its likely script if it has none.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::likely_script;
+/// use icu_locid::{langid, subtags::script};
+///
+/// assert_eq!(likely_script(&langid!("sr")), Some(script!("Cyrl")));
+/// assert_eq!(likely_script(&langid!("sr-Latn")), Some(script!("Latn")));
+/// ```
+#[must_use]
+pub fn likely_script(langid: &LanguageIdentifier) -> Option<Script> {
+    maximize(langid, MaximizeDepth::Script).script
+}
+
+/// Returns an identifier with only the language of the given one, like `en`
+/// for `en-Latn-US`.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::language_only;
+/// use icu_locid::langid;
+///
+/// assert_eq!(language_only(&langid!("en-Latn-US")), langid!("en"));
+/// ```
+#[must_use]
+pub fn language_only(langid: &LanguageIdentifier) -> LanguageIdentifier {
+    langid.language.into()
+}
+
+/// Returns an identifier with only the language and the script of the given
+/// one, like `en-Latn` for `en-Latn-US`.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::language_and_script;
+/// use icu_locid::langid;
+///
+/// assert_eq!(language_and_script(&langid!("en-Latn-US")), langid!("en-Latn"));
+/// assert_eq!(language_and_script(&langid!("en-US")), langid!("en"));
+/// ```
+#[must_use]
+pub fn language_and_script(langid: &LanguageIdentifier) -> LanguageIdentifier {
+    (langid.language, langid.script, None).into()
+}
+
+/// Returns an identifier with only the language and the region of the given
+/// one, like `en-US` for `en-Latn-US-posix`.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::language_and_region;
+/// use icu_locid::langid;
+///
+/// assert_eq!(language_and_region(&langid!("en-Latn-US-posix")), langid!("en-US"));
+/// ```
+#[must_use]
+pub fn language_and_region(langid: &LanguageIdentifier) -> LanguageIdentifier {
+    (langid.language, None, langid.region).into()
+}
+
+/// Returns a copy of the identifier with its region replaced, validating it.
+///
+/// Together with [`with_script`] and [`with_variants`], this builds candidate
+/// identifiers from a base one, and can be chained with [`Result::and_then`].
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::{with_region, with_script};
+/// use icu_locid::langid;
+///
+/// let base = langid!("sr");
+/// assert_eq!(
+///     with_region(&base, "RS").and_then(|langid| with_script(&langid, "Latn")),
+///     Ok(langid!("sr-Latn-RS"))
+/// );
+/// assert!(with_region(&base, "Serbia").is_err());
+/// ```
+pub fn with_region(
+    langid: &LanguageIdentifier,
+    region: &str,
+) -> Result<LanguageIdentifier, ParserError> {
+    Ok(LanguageIdentifier {
+        region: Some(Region::try_from_bytes(region.as_bytes())?),
+        ..langid.clone()
+    })
+}
+
+/// Returns a copy of the identifier with its script replaced, validating it.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::with_script;
+/// use icu_locid::langid;
+///
+/// assert_eq!(with_script(&langid!("zh-TW"), "Hant"), Ok(langid!("zh-Hant-TW")));
+/// assert!(with_script(&langid!("zh-TW"), "Han").is_err());
+/// ```
+pub fn with_script(
+    langid: &LanguageIdentifier,
+    script: &str,
+) -> Result<LanguageIdentifier, ParserError> {
+    Ok(LanguageIdentifier {
+        script: Some(Script::try_from_bytes(script.as_bytes())?),
+        ..langid.clone()
+    })
+}
+
+/// Returns a copy of the identifier with its variants replaced, validating
+/// them. The variants are sorted and deduplicated, like when parsing a tag.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::with_variants;
+/// use icu_locid::langid;
+///
+/// assert_eq!(with_variants(&langid!("ca-ES"), &["valencia"]), Ok(langid!("ca-ES-valencia")));
+/// assert_eq!(with_variants(&langid!("ca-ES-valencia"), &[]), Ok(langid!("ca-ES")));
+/// assert!(with_variants(&langid!("ca-ES"), &["va"]).is_err());
+/// ```
+pub fn with_variants(
+    langid: &LanguageIdentifier,
+    variants: &[&str],
+) -> Result<LanguageIdentifier, ParserError> {
+    Ok(LanguageIdentifier {
+        variants: parse_variants(variants)?,
+        ..langid.clone()
+    })
+}
+
+fn parse_variants(variants: &[&str]) -> Result<Variants, ParserError> {
+    let mut variants = variants
+        .iter()
+        .map(|s| Variant::try_from_bytes(s.as_bytes()))
+        .collect::<Result<Vec<_>, _>>()?;
+    variants.sort_unstable();
+    variants.dedup();
+    Ok(Variants::from_vec_unchecked(variants))
+}
+
+/// Constructs a [`LanguageIdentifier`] from its subtags, validating each of them.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::from_parts;
+/// use icu_locid::langid;
+///
+/// assert_eq!(
+///     from_parts("sr", Some("Latn"), Some("RS"), &[]),
+///     Ok(langid!("sr-Latn-RS"))
+/// );
+/// assert!(from_parts("sr", Some("Latin"), None, &[]).is_err());
+/// ```
+pub fn from_parts(
+    language: &str,
+    script: Option<&str>,
+    region: Option<&str>,
+    variants: &[&str],
+) -> Result<LanguageIdentifier, ParserError> {
+    let language = Language::try_from_bytes(language.as_bytes())?;
+    let script = script
+        .map(|s| Script::try_from_bytes(s.as_bytes()))
+        .transpose()?;
+    let region = region
+        .map(|s| Region::try_from_bytes(s.as_bytes()))
+        .transpose()?;
+    let variants = parse_variants(variants)?;
+
+    Ok(LanguageIdentifier {
+        language,
+        script,
+        region,
+        variants,
+    })
+}
+
+/// Returns the language, script and region subtags of the identifier, without
+/// its variants, for destructuring and pattern matching.
+///
+/// Together with [`from_parts`], this converts between an identifier and a
+/// tuple of its subtags.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::as_parts;
+/// use icu_locid::langid;
+///
+/// match as_parts(&langid!("sr-Latn-RS")) {
+///     ("sr", Some("Latn"), region) => assert_eq!(region, Some("RS")),
+///     _ => unreachable!(),
+/// }
+/// ```
+#[must_use]
+pub fn as_parts(langid: &LanguageIdentifier) -> (&str, Option<&str>, Option<&str>) {
+    (
+        langid.language.as_str(),
+        langid.script.as_ref().map(|script| script.as_str()),
+        langid.region.as_ref().map(|region| region.as_str()),
+    )
+}
+
+/// Grandfathered tags from [RFC5646](https://www.rfc-editor.org/rfc/rfc5646.html#section-2.2.8)
+/// with a preferred value, sorted by the tag.
+///
+/// The redundant `sgn-*` tags of sign languages, like `sgn-US`, are listed as
+/// well, with the preferred values of CLDR's `languageAlias` data.
+const GRANDFATHERED: &[(&str, LanguageIdentifier)] = &[
+    ("art-lojban", langid!("jbo")),
+    ("en-gb-oed", langid!("en-GB-oxendict")),
+    ("i-ami", langid!("ami")),
+    ("i-bnn", langid!("bnn")),
+    ("i-hak", langid!("hak")),
+    ("i-klingon", langid!("tlh")),
+    ("i-lux", langid!("lb")),
+    ("i-navajo", langid!("nv")),
+    ("i-pwn", langid!("pwn")),
+    ("i-tao", langid!("tao")),
+    ("i-tay", langid!("tay")),
+    ("i-tsu", langid!("tsu")),
+    ("no-bok", langid!("nb")),
+    ("no-nyn", langid!("nn")),
+    ("sgn-be-fr", langid!("sfb")),
+    ("sgn-be-nl", langid!("vgt")),
+    ("sgn-br", langid!("bzs")),
+    ("sgn-ch-de", langid!("sgg")),
+    ("sgn-co", langid!("csn")),
+    ("sgn-de", langid!("gsg")),
+    ("sgn-dk", langid!("dsl")),
+    ("sgn-es", langid!("ssp")),
+    ("sgn-fr", langid!("fsl")),
+    ("sgn-gb", langid!("bfi")),
+    ("sgn-gr", langid!("gss")),
+    ("sgn-ie", langid!("isg")),
+    ("sgn-it", langid!("ise")),
+    ("sgn-jp", langid!("jsl")),
+    ("sgn-mx", langid!("mfs")),
+    ("sgn-ni", langid!("ncs")),
+    ("sgn-nl", langid!("dse")),
+    ("sgn-no", langid!("nsi")),
+    ("sgn-pt", langid!("psr")),
+    ("sgn-se", langid!("swl")),
+    ("sgn-us", langid!("ase")),
+    ("sgn-za", langid!("sfs")),
+    ("zh-guoyu", langid!("cmn")),
+    ("zh-hakka", langid!("hak")),
+    ("zh-min-nan", langid!("nan")),
+    ("zh-xiang", langid!("hsn")),
+];
+
+/// Returns the preferred value of a grandfathered tag, like `tlh` for `i-klingon`,
+/// or `None` if the tag is not grandfathered.
+///
+/// Most grandfathered tags can't be parsed as a [`LanguageIdentifier`], and
+/// regular ones, like `zh-guoyu`, would be parsed with a variant, so the tag
+/// parsing functions of this crate, like
+/// [`convert_vec_str_to_langids`](crate::convert_vec_str_to_langids), replace
+/// them with their preferred value. Tags without one, like `zh-min`, are
+/// parsed as usual.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::grandfathered;
+/// use icu_locid::langid;
+///
+/// assert_eq!(grandfathered("i-klingon"), Some(langid!("tlh")));
+/// assert_eq!(grandfathered("sgn-US"), Some(langid!("ase")));
+/// assert_eq!(grandfathered("en-US"), None);
+/// ```
+#[must_use]
+pub fn grandfathered(tag: &str) -> Option<LanguageIdentifier> {
+    let normalized = trim_tag(tag.as_bytes()).iter().map(|b| match b {
+        b'_' => b'-',
+        b => b.to_ascii_lowercase(),
+    });
+    GRANDFATHERED
+        .binary_search_by(|(t, _)| t.bytes().cmp(normalized.clone()))
+        .ok()
+        .map(|idx| GRANDFATHERED[idx].1.clone())
+}
+
+/// Returns whether the tag is `root`, the name of the root locale in CLDR,
+/// which is parsed as `und`.
+fn is_root_tag(tag: &[u8]) -> bool {
+    trim_tag(tag).eq_ignore_ascii_case(b"root")
+}
+
+/// Parses a tag, replacing a grandfathered tag with its preferred value, and
+/// `root` with `und`.
+pub(crate) fn parse_tag(tag: &[u8]) -> Result<LanguageIdentifier, ParserError> {
+    if is_root_tag(tag) {
+        return Ok(LanguageIdentifier::UND);
+    }
+    match std::str::from_utf8(tag).ok().and_then(grandfathered) {
+        Some(langid) => Ok(langid),
+        None => LanguageIdentifier::try_from_bytes(trim_tag(tag)),
+    }
+}
+
+/// Parses a tag into a [`Locale`], replacing a grandfathered tag with its
+/// preferred value, and `root` with `und`.
+pub(crate) fn parse_locale_tag(tag: &[u8]) -> Result<Locale, ParserError> {
+    if is_root_tag(tag) {
+        return Ok(Locale::UND);
+    }
+    match std::str::from_utf8(tag).ok().and_then(grandfathered) {
+        Some(langid) => Ok(langid.into()),
+        None => Locale::try_from_bytes(trim_tag(tag)),
+    }
+}
+
+/// Deprecated language codes and their replacements, based on CLDR's
+/// `languageAlias` data, sorted by the deprecated code.
+static LANGUAGE_ALIASES: &[(Language, Language)] = &[
+    (language!("in"), language!("id")),
+    (language!("iw"), language!("he")),
+    (language!("ji"), language!("yi")),
+    (language!("jw"), language!("jv")),
+    (language!("mo"), language!("ro")),
+];
+
+/// Replaces deprecated language and region codes of the identifier with their
+/// successors, like `iw-YU` → `he-RS`, returning whether it has been modified.
+///
+/// Numeric codes of countries are replaced with their alpha-2 codes, like
+/// `es-484` → `es-MX`.
+///
+/// Sign languages tagged with a region, like `sgn-US`, are replaced with
+/// their own language code, like `ase`.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::canonicalize_aliases;
+/// use icu_locid::langid;
+///
+/// let mut langid = langid!("iw-IL");
+/// assert!(canonicalize_aliases(&mut langid));
+/// assert_eq!(langid, langid!("he-IL"));
+/// ```
+pub fn canonicalize_aliases(langid: &mut LanguageIdentifier) -> bool {
+    if langid.language == language!("sgn") {
+        if let Some(preferred) = grandfathered(&langid.to_string()) {
+            *langid = preferred;
+            return true;
+        }
+    }
+    let mut modified = false;
+    if let Ok(idx) = LANGUAGE_ALIASES.binary_search_by(|(l, _)| l.cmp(&langid.language)) {
+        langid.language = LANGUAGE_ALIASES[idx].1;
+        modified = true;
+    }
+    if langid.region.is_some() {
+        modified |= region_aliases::canonicalize_region(langid, &LocaleExpander::new());
+    }
+    modified
+}
+
+/// Returns whether two identifiers are equal once deprecated language and
+/// region codes have been replaced, like `iw-IL` and `he-IL`.
+///
+/// Unlike negotiation, no subtag is treated as a range, so `he` is not
+/// equivalent to `he-IL`.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::is_equivalent;
+/// use icu_locid::langid;
+///
+/// assert!(is_equivalent(&langid!("iw-IL"), &langid!("he-IL")));
+/// assert!(!is_equivalent(&langid!("iw"), &langid!("he-IL")));
+/// ```
+#[must_use]
+pub fn is_equivalent(langid1: &LanguageIdentifier, langid2: &LanguageIdentifier) -> bool {
+    let (mut langid1, mut langid2) = (langid1.clone(), langid2.clone());
+    canonicalize_aliases(&mut langid1);
+    canonicalize_aliases(&mut langid2);
+    langid1 == langid2
+}
+
+/// Returns whether two identifiers have the same language, script and region,
+/// regardless of their variants, like `de-DE-1901` and `de-DE`.
+///
+/// Unlike negotiation, no subtag is treated as a range, so `de` does not match
+/// `de-DE`.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::matches_ignoring_variants;
+/// use icu_locid::langid;
+///
+/// assert!(matches_ignoring_variants(&langid!("de-DE-1901"), &langid!("de-DE")));
+/// assert!(!matches_ignoring_variants(&langid!("de-DE"), &langid!("de-AT")));
+/// ```
+#[must_use]
+pub fn matches_ignoring_variants(
+    langid1: &LanguageIdentifier,
+    langid2: &LanguageIdentifier,
+) -> bool {
+    langid1.language == langid2.language
+        && langid1.script == langid2.script
+        && langid1.region == langid2.region
+}
+
+/// Returns whether two identifiers have the same language and script,
+/// regardless of their regions and variants, like `en-US` and `en-GB`.
+///
+/// Unlike negotiation, the regions are ignored on both sides, and no likely
+/// subtags are added, so `sr-RS` does not match `sr-Cyrl-RS`.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::matches_language_script;
+/// use icu_locid::langid;
+///
+/// assert!(matches_language_script(&langid!("en-US"), &langid!("en-GB")));
+/// assert!(!matches_language_script(&langid!("en-US"), &langid!("fr-FR")));
+/// ```
+#[must_use]
+pub fn matches_language_script(langid1: &LanguageIdentifier, langid2: &LanguageIdentifier) -> bool {
+    langid1.language == langid2.language && langid1.script == langid2.script
+}
+
+/// Parses an [RFC4647](https://www.ietf.org/rfc/rfc4647.txt) extended language
+/// range, like `en-*-US`, into a [`LanguageIdentifier`] to be used as an
+/// available locale.
+///
+/// A wildcard subtag is removed, since available locales are matched as ranges,
+/// where a missing subtag matches any value. A wildcard language becomes `und`,
+/// which matches any language.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::parse_range;
+/// use icu_locid::langid;
+///
+/// assert_eq!(parse_range("en-*-US"), Ok(langid!("en-US")));
+/// assert_eq!(parse_range("*-Latn"), Ok(langid!("und-Latn")));
+/// ```
+pub fn parse_range(range: &str) -> Result<LanguageIdentifier, ParserError> {
+    let range = std::str::from_utf8(trim_tag(range.as_bytes())).unwrap_or_default();
+    let mut subtags = range.split(SEPARATORS);
+    let language = match subtags.next() {
+        Some("*") => "und",
+        Some(language) => language,
+        None => return Err(ParserError::InvalidLanguage),
+    };
+    let tag = std::iter::once(language)
+        .chain(subtags.filter(|s| *s != "*"))
+        .collect::<Vec<_>>()
+        .join("-");
+    parse_tag(tag.as_bytes())
+}
+
+/// Parses the longest valid prefix of a tag, ignoring the rest of it.
+///
+/// This function never fails. If no prefix of the tag is valid, `und` is returned.
+/// Since any invalid subtag is silently dropped together with all subtags
+/// following it, this is only suitable for best effort handling of user input.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::parse_lossy;
+/// use icu_locid::langid;
+///
+/// assert_eq!(parse_lossy("en-US-@#$"), langid!("en-US"));
+/// assert_eq!(parse_lossy("@#$"), langid!("und"));
+/// ```
+#[must_use]
+pub fn parse_lossy(tag: &str) -> LanguageIdentifier {
+    if let Some(langid) = grandfathered(tag) {
+        return langid;
+    }
+    let tag = trim_tag(tag.as_bytes());
+    let tag = std::str::from_utf8(tag).unwrap_or_default();
+    truncations(tag)
+        .find_map(|t| LanguageIdentifier::try_from_bytes(t.as_bytes()).ok())
+        .unwrap_or_default()
+}
+
+/// Returns whether the tag, once parsed, is equal to the identifier.
+///
+/// The tag is compared subtag by subtag, without allocating, so this is
+/// cheaper than parsing the tag or serializing the identifier.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::eq_canonical_str;
+/// use icu_locid::langid;
+///
+/// assert!(eq_canonical_str(&langid!("sr-Latn-RS"), "SR_latn_rs"));
+/// assert!(!eq_canonical_str(&langid!("sr-Latn-RS"), "sr-Latn"));
+/// ```
+#[must_use]
+pub fn eq_canonical_str(langid: &LanguageIdentifier, tag: &str) -> bool {
+    let tag = std::str::from_utf8(trim_tag(tag.as_bytes())).unwrap_or_default();
+    if langid.normalizing_eq(tag) {
+        return true;
+    }
+    // Variants are sorted when parsed, so the tag may list them in another order.
+    langid.variants.len() > 1
+        && LanguageIdentifier::try_from_bytes(tag.as_bytes()).is_ok_and(|t| &t == langid)
+}
+
+/// Serializes a language identifier or a locale using a custom subtag separator,
+/// like the underscore expected by gettext and POSIX locales.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::to_string_with_separator;
+/// use icu_locid::langid;
+///
+/// assert_eq!(to_string_with_separator(&langid!("en-US"), '_'), "en_US");
+/// ```
+#[must_use]
+pub fn to_string_with_separator<L: Display + ?Sized>(langid: &L, separator: char) -> String {
+    langid
+        .to_string()
+        .replace('-', separator.encode_utf8(&mut [0; 4]))
+}
+
+/// Formats language tags, like the negotiated locales, as a colon-separated
+/// list of POSIX locale names, which is the format of the `LANGUAGE` variable
+/// read by gettext.
+///
+/// The `Latn` and `Cyrl` scripts are written as the `@latin` and `@cyrillic`
+/// modifiers, while other scripts and variants are dropped, as gettext has no
+/// subtags for them. Tags which fail to parse and repeated locales are skipped.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::to_gettext_language_chain;
+///
+/// assert_eq!(to_gettext_language_chain(&["de", "en-US", "en"]), "de:en_US:en");
+/// assert_eq!(to_gettext_language_chain(&["sr-Latn-RS"]), "sr_RS@latin");
+/// ```
+#[must_use]
+pub fn to_gettext_language_chain(supported: &[&str]) -> String {
+    let mut names: Vec<String> = vec![];
+    for tag in supported {
+        let Ok(mut langid) = parse_tag(tag.as_bytes()) else {
+            continue;
+        };
+        let modifier = match langid.script {
+            Some(s) if s == script!("Latn") => Some("latin"),
+            Some(s) if s == script!("Cyrl") => Some("cyrillic"),
+            _ => None,
+        };
+        langid.script = None;
+        langid.variants.clear();
+
+        let mut name = to_string_with_separator(&langid, '_');
+        if let Some(modifier) = modifier {
+            name.push('@');
+            name.push_str(modifier);
+        }
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names.join(":")
+}
+
+/// Returns whether the locale has the extension introduced by `singleton`,
+/// like `'u'` for the unicode extension or `'x'` for the private use one.
+///
+/// The singleton is case-insensitive.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::has_extension;
+/// use icu_locid::locale;
+///
+/// assert!(has_extension(&locale!("th-TH-u-ca-buddhist"), 'u'));
+/// assert!(!has_extension(&locale!("th-TH-u-ca-buddhist"), 't'));
+/// ```
+#[must_use]
+pub fn has_extension(locale: &Locale, singleton: char) -> bool {
+    let extensions = &locale.extensions;
+    match singleton.to_ascii_lowercase() {
+        'u' => !extensions.unicode.is_empty(),
+        't' => !extensions.transform.is_empty(),
+        'x' => !extensions.private.is_empty(),
+        singleton => extensions.other.iter().any(|o| o.get_ext() == singleton),
+    }
+}
+
+/// Returns the subtags of the extension introduced by `singleton`, without
+/// the singleton itself, like `ca-buddhist` for `'u'` in `th-TH-u-ca-buddhist`.
+///
+/// The extensions of a [`Locale`] are stored parsed, so the subtags are
+/// serialized in their canonical form.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::langid::extension;
+/// use icu_locid::Locale;
+///
+/// let locale: Locale = "de-DE-u-co-phonebk-x-linux".parse().unwrap();
+/// assert_eq!(extension(&locale, 'u').as_deref(), Some("co-phonebk"));
+/// assert_eq!(extension(&locale, 'X').as_deref(), Some("linux"));
+/// assert_eq!(extension(&locale, 't'), None);
+/// ```
+#[must_use]
+pub fn extension(locale: &Locale, singleton: char) -> Option<String> {
+    if !has_extension(locale, singleton) {
+        return None;
+    }
+    let extensions = &locale.extensions;
+    let extension = match singleton.to_ascii_lowercase() {
+        'u' => extensions.unicode.to_string(),
+        't' => extensions.transform.to_string(),
+        'x' => extensions.private.to_string(),
+        singleton => extensions
+            .other
+            .iter()
+            .find(|o| o.get_ext() == singleton)?
+            .to_string(),
+    };
+    // Strip the singleton and its separator.
+    Some(extension[2..].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grandfathered_sort() {
+        for v in GRANDFATHERED.windows(2) {
+            let (v1, v2) = (&v[0], &v[1]);
+            assert!(v1.0 < v2.0, "Tag \"{}\" is placed after \"{}\"", v1.0, v2.0);
+        }
+    }
+
+    #[test]
+    fn test_language_aliases_sort() {
+        for v in LANGUAGE_ALIASES.windows(2) {
+            let (v1, v2) = (v[0], v[1]);
+            assert!(
+                v1.0 < v2.0,
+                "Language \"{}\" is placed after \"{}\"",
+                v1.0,
+                v2.0
+            );
+        }
+    }
+}
@@ -14,15 +14,67 @@
 //! BCP47 like [rust-language-tags](https://github.com/pyfisch/rust-language-tags),
 //! but is arguably a better option for use cases involving operations on
 //! language tags and for language negotiation.
+//!
+//! Negotiation operates on anything that can be referenced as a
+//! [`LanguageIdentifier`], including [`icu_locid::Locale`]. Extensions of a
+//! `Locale`, like the unicode `-u-` or the transform `-t-` extension, are
+//! preserved in the result, but ignored during matching.
+//!
+//! Helpers parsing lists of strings, like [`convert_vec_str_to_langids`],
+//! ignore surrounding whitespace and trailing separators, so `" en-US- "`
+//! is parsed as `en-US`.
+//!
+//! Language tags are ASCII-only. Tags containing any other characters are
+//! rejected with a [`LangugeIdentifierParserError`], and skipped by the
+//! lossy helpers and by [`parse_accepted_languages`].
+
+use std::borrow::Borrow;
+use std::fmt::{Display, Write};
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 
 pub mod accepted_languages;
+pub mod available;
+#[cfg(feature = "env")]
+pub mod env;
+pub mod langid;
 pub mod negotiate;
+#[cfg(feature = "async")]
+pub mod stream;
 
+pub use accepted_languages::negotiate_from_accept_language;
 pub use accepted_languages::parse as parse_accepted_languages;
+#[cfg(feature = "env")]
+pub use env::negotiate_from_env;
+pub use langid::to_gettext_language_chain;
+pub use negotiate::coverage;
+pub use negotiate::explain;
+pub use negotiate::filter_available;
+pub use negotiate::match_level;
+pub use negotiate::matches_range;
+pub use negotiate::negotiate_best_within;
+pub use negotiate::negotiate_grouped;
 pub use negotiate::negotiate_languages;
+pub use negotiate::negotiate_languages_with_defaults;
+pub use negotiate::negotiate_map;
+pub use negotiate::negotiate_one;
+pub use negotiate::negotiate_pairs;
+pub use negotiate::negotiate_ranked;
+pub use negotiate::negotiate_result;
+pub use negotiate::negotiate_top_n;
+pub use negotiate::negotiate_with_confidence;
+pub use negotiate::unserved;
+pub use negotiate::validate_available;
 pub use negotiate::NegotiationStrategy;
+pub use negotiate::{
+    AvailabilityWarning, AvailablePreference, Confidence, DefaultBehavior, DefaultPosition,
+    MacrolanguageFallback, MatchKind, MatchedLocale, Negotiated, NegotiationResult, Negotiator,
+    NegotiatorConfig,
+};
+#[cfg(feature = "async")]
+pub use stream::negotiate_stream;
 
-pub use icu_locid::{LanguageIdentifier, ParserError as LangugeIdentifierParserError};
+pub use icu_locid::{LanguageIdentifier, Locale, ParserError as LangugeIdentifierParserError};
 
 pub fn convert_vec_str_to_langids<'a, I, J>(
     input: I,
@@ -33,10 +85,11 @@ where
 {
     input
         .into_iter()
-        .map(|s| LanguageIdentifier::try_from_bytes(s.as_ref()))
+        .map(|s| langid::parse_tag(s.as_ref()))
         .collect()
 }
 
+#[must_use]
 pub fn convert_vec_str_to_langids_lossy<'a, I, J>(input: I) -> Vec<LanguageIdentifier>
 where
     I: IntoIterator<Item = J>,
@@ -44,6 +97,377 @@ where
 {
     input
         .into_iter()
-        .filter_map(|t| LanguageIdentifier::try_from_bytes(t.as_ref()).ok())
+        .filter_map(|t| langid::parse_tag(t.as_ref()).ok())
+        .collect()
+}
+
+/// Parses a list of strings into [`Locale`]s, preserving their extensions.
+///
+/// The result can be passed to [`negotiate_languages`] like a list of
+/// [`LanguageIdentifier`]s.
+pub fn convert_vec_str_to_locales<'a, I, J>(
+    input: I,
+) -> Result<Vec<Locale>, LangugeIdentifierParserError>
+where
+    I: IntoIterator<Item = J>,
+    J: AsRef<[u8]> + 'a,
+{
+    input
+        .into_iter()
+        .map(|s| langid::parse_locale_tag(s.as_ref()))
+        .collect()
+}
+
+#[must_use]
+pub fn convert_vec_str_to_locales_lossy<'a, I, J>(input: I) -> Vec<Locale>
+where
+    I: IntoIterator<Item = J>,
+    J: AsRef<[u8]> + 'a,
+{
+    input
+        .into_iter()
+        .filter_map(|t| langid::parse_locale_tag(t.as_ref()).ok())
+        .collect()
+}
+
+/// A locale tag which failed to parse, reported by [`negotiate_languages_checked`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidTag {
+    pub tag: String,
+    pub error: LangugeIdentifierParserError,
+}
+
+/// Negotiates like [`negotiate_languages`], but takes the available and the
+/// default locales as tags, and reports every tag which failed to parse
+/// instead of skipping it.
+///
+/// This is meant for catching a misconfigured list of available locales, like a
+/// typo in a locale directory name, at startup.
+///
+/// Grandfathered tags, like `i-klingon`, are negotiated using their preferred
+/// value, but returned in their original form.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::{convert_vec_str_to_langids_lossy, negotiate_languages_checked};
+/// use fluent_langneg::NegotiationStrategy;
+///
+/// let requested = convert_vec_str_to_langids_lossy(&["de-AT", "fr"]);
+///
+/// let supported = negotiate_languages_checked(
+///     &requested,
+///     &["fr", "de", "en-US"],
+///     Some(&"en-US"),
+///     NegotiationStrategy::Filtering,
+/// );
+/// assert_eq!(supported, Ok(vec![&"de", &"fr", &"en-US"]));
+///
+/// let errors = negotiate_languages_checked(
+///     &requested,
+///     &["fr", "de_ATT", "en-US"],
+///     None,
+///     NegotiationStrategy::Filtering,
+/// )
+/// .unwrap_err();
+/// assert_eq!(errors[0].tag, "de_ATT");
+/// ```
+pub fn negotiate_languages_checked<'a, R, J>(
+    requested: &[R],
+    available: &'a [J],
+    default: Option<&'a J>,
+    strategy: NegotiationStrategy,
+) -> Result<Vec<&'a J>, Vec<InvalidTag>>
+where
+    R: AsRef<LanguageIdentifier>,
+    J: AsRef<str>,
+{
+    let mut errors = vec![];
+    let mut parse = |tag: &J| {
+        langid::parse_tag(tag.as_ref().as_bytes())
+            .map_err(|error| {
+                errors.push(InvalidTag {
+                    tag: tag.as_ref().to_string(),
+                    error,
+                })
+            })
+            .ok()
+    };
+    let langids: Vec<LanguageIdentifier> = available.iter().filter_map(&mut parse).collect();
+    let default_langid = default.and_then(parse);
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let supported = negotiate_languages(requested, &langids, default_langid.as_ref(), strategy);
+    Ok(supported
+        .into_iter()
+        .map(
+            |langid| match langids.iter().position(|l| std::ptr::eq(l, langid)) {
+                Some(idx) => &available[idx],
+                None => default.expect("Only the default locale is not in the available list."),
+            },
+        )
+        .collect())
+}
+
+/// Returns the canonical form of each tag, without duplicates, like
+/// `Intl.getCanonicalLocales` in JavaScript.
+///
+/// Extensions are preserved, and variants are lowercased and sorted
+/// alphabetically, as in CLDR, so `de-1996-1901` and `de-1901-1996` produce
+/// the same tag. The first tag which failed to parse is reported as an error.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::canonical_locales;
+///
+/// assert_eq!(
+///     canonical_locales(["EN-us", "fr-FR", "en_US"]),
+///     Ok(vec!["en-US".to_string(), "fr-FR".to_string()])
+/// );
+/// assert!(canonical_locales(["en-US", "en-"]).is_ok());
+/// assert!(canonical_locales(["en-US", "@#$"]).is_err());
+/// ```
+pub fn canonical_locales<'a, I, J>(input: I) -> Result<Vec<String>, LangugeIdentifierParserError>
+where
+    I: IntoIterator<Item = J>,
+    J: AsRef<[u8]> + 'a,
+{
+    let mut result: Vec<String> = vec![];
+    for tag in input {
+        let tag = langid::parse_locale_tag(tag.as_ref())?.to_string();
+        if !result.contains(&tag) {
+            result.push(tag);
+        }
+    }
+    Ok(result)
+}
+
+/// A [`Locale`] together with its canonical tag, which can be borrowed as a
+/// `&str`, like for looking up a `HashMap` keyed by locales with a tag.
+///
+/// `Locale` is defined by `icu_locid`, so it can't implement [`Borrow<str>`]
+/// itself. The tag is computed once, when the locale is wrapped, which
+/// costs an allocation per locale but makes borrowing it free afterwards.
+/// Locales are equal if and only if their canonical tags are, so equality and
+/// hashing are those of the tag, as required by `Borrow`.
+///
+/// It can be negotiated like a `Locale`.
+///
+/// # Example:
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use fluent_langneg::CanonicalLocale;
+///
+/// let mut bundles: HashMap<CanonicalLocale, &str> = HashMap::new();
+/// bundles.insert("en_us".parse().unwrap(), "en-US.ftl");
+///
+/// assert_eq!(bundles.get("en-US"), Some(&"en-US.ftl"));
+/// assert_eq!(bundles.get("en-GB"), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CanonicalLocale {
+    tag: String,
+    locale: Locale,
+}
+
+impl CanonicalLocale {
+    #[must_use]
+    pub fn new(locale: Locale) -> Self {
+        Self {
+            tag: locale.to_string(),
+            locale,
+        }
+    }
+
+    /// Returns the canonical tag of the locale.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.tag
+    }
+
+    #[must_use]
+    pub fn locale(&self) -> &Locale {
+        &self.locale
+    }
+
+    #[must_use]
+    pub fn into_locale(self) -> Locale {
+        self.locale
+    }
+}
+
+impl From<Locale> for CanonicalLocale {
+    fn from(locale: Locale) -> Self {
+        Self::new(locale)
+    }
+}
+
+impl From<LanguageIdentifier> for CanonicalLocale {
+    fn from(langid: LanguageIdentifier) -> Self {
+        Self::new(langid.into())
+    }
+}
+
+impl FromStr for CanonicalLocale {
+    type Err = LangugeIdentifierParserError;
+
+    fn from_str(tag: &str) -> Result<Self, Self::Err> {
+        langid::parse_locale_tag(tag.as_bytes()).map(Self::new)
+    }
+}
+
+impl PartialEq for CanonicalLocale {
+    fn eq(&self, other: &Self) -> bool {
+        self.tag == other.tag
+    }
+}
+
+impl Eq for CanonicalLocale {}
+
+impl Hash for CanonicalLocale {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.tag.hash(state);
+    }
+}
+
+impl Borrow<str> for CanonicalLocale {
+    fn borrow(&self) -> &str {
+        &self.tag
+    }
+}
+
+impl AsRef<str> for CanonicalLocale {
+    fn as_ref(&self) -> &str {
+        &self.tag
+    }
+}
+
+impl AsRef<LanguageIdentifier> for CanonicalLocale {
+    fn as_ref(&self) -> &LanguageIdentifier {
+        &self.locale.id
+    }
+}
+
+impl Display for CanonicalLocale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.tag)
+    }
+}
+
+/// Parses a list of tags, like the names of locale directories, into a list of
+/// unique [`LanguageIdentifier`]s to be used as available locales.
+///
+/// Tags which are equal once parsed, like `en_US` and `en-us`, are kept only
+/// once, in the position of their first occurrence. Tags which failed to parse
+/// are returned separately for reporting.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::available_from_tags;
+/// use icu_locid::langid;
+///
+/// let (available, invalid) = available_from_tags(["en-US", "de", "en_us", ".DS_Store"]);
+/// assert_eq!(available, &[langid!("en-US"), langid!("de")]);
+/// assert_eq!(invalid, &[".DS_Store"]);
+/// ```
+pub fn available_from_tags<I, J>(input: I) -> (Vec<LanguageIdentifier>, Vec<J>)
+where
+    I: IntoIterator<Item = J>,
+    J: AsRef<[u8]>,
+{
+    let mut available: Vec<LanguageIdentifier> = vec![];
+    let mut invalid = vec![];
+    for tag in input {
+        match langid::parse_tag(tag.as_ref()) {
+            Ok(langid) => {
+                if !available.contains(&langid) {
+                    available.push(langid);
+                }
+            }
+            Err(_) => invalid.push(tag),
+        }
+    }
+    (available, invalid)
+}
+
+/// Joins language tags into a single string, separated by commas without spaces.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::{convert_vec_str_to_langids_lossy, join_tags};
+///
+/// let supported = convert_vec_str_to_langids_lossy(&["fr", "en-US", "en-GB"]);
+/// assert_eq!(join_tags(&supported), "fr,en-US,en-GB");
+/// ```
+#[must_use]
+pub fn join_tags<I, J>(tags: I) -> String
+where
+    I: IntoIterator<Item = J>,
+    J: Display,
+{
+    let mut result = String::new();
+    for (idx, tag) in tags.into_iter().enumerate() {
+        if idx > 0 {
+            result.push(',');
+        }
+        write!(result, "{}", tag).expect("Writing to a String can't fail.");
+    }
+    result
+}
+
+/// Negotiates a comma-separated list of requested locales, like `"pl,fr,en-US"`,
+/// against available locale tags.
+///
+/// Each requested locale is trimmed, and empty or invalid ones are skipped, as
+/// are invalid available and default tags.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::{negotiate_languages_str, NegotiationStrategy};
+///
+/// let supported = negotiate_languages_str(
+///     " pl, fr,,en-US ",
+///     &["it", "fr", "en-GB"],
+///     Some("en-GB"),
+///     NegotiationStrategy::Filtering,
+/// );
+/// assert_eq!(supported, &["fr", "en-GB"]);
+/// ```
+#[must_use]
+pub fn negotiate_languages_str<'a>(
+    requested: &str,
+    available: &[&'a str],
+    default: Option<&'a str>,
+    strategy: NegotiationStrategy,
+) -> Vec<&'a str> {
+    let requested = convert_vec_str_to_langids_lossy(
+        requested
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty()),
+    );
+    let parse = |tag: &str| langid::parse_tag(tag.as_bytes()).ok();
+    let (langids, tags): (Vec<LanguageIdentifier>, Vec<&str>) = available
+        .iter()
+        .filter_map(|tag| Some((parse(tag)?, *tag)))
+        .unzip();
+    let default_langid = default.and_then(parse);
+
+    negotiate_languages(&requested, &langids, default_langid.as_ref(), strategy)
+        .into_iter()
+        .map(
+            |langid| match langids.iter().position(|l| std::ptr::eq(l, langid)) {
+                Some(idx) => tags[idx],
+                None => default.expect("Only the default locale is not in the available list."),
+            },
+        )
         .collect()
 }
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use icu_locid::{
+    subtags::{Language, Region, Script},
+    LanguageIdentifier,
+};
+
+use super::{
+    normalize_weights, AvailablePreference, DefaultBehavior, DefaultPosition,
+    MacrolanguageFallback, MatchKind, NegotiationStrategy, Negotiator,
+};
+
+/// The settings of a [`Negotiator`], for storing a negotiation policy in a
+/// configuration file.
+///
+/// With the `serde` feature, it can be serialized and deserialized. Missing
+/// fields take their default values, which are the ones of
+/// [`Negotiator::new`] with the `Filtering` strategy.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::{MatchKind, Negotiator, NegotiationStrategy, NegotiatorConfig};
+///
+/// let config = NegotiatorConfig {
+///     strategy: NegotiationStrategy::Lookup,
+///     min_acceptable_level: Some(MatchKind::AvailableRange),
+///     ..Default::default()
+/// };
+/// let negotiator = Negotiator::from_config(config.clone());
+/// assert_eq!(negotiator.to_config().min_acceptable_level, config.min_acceptable_level);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct NegotiatorConfig {
+    pub strategy: NegotiationStrategy,
+    /// The behavior of the default locale, or `None` for the one of the strategy.
+    pub default_behavior: Option<DefaultBehavior>,
+    pub default_position: DefaultPosition,
+    pub min_acceptable_level: Option<MatchKind>,
+    pub consume_matched: bool,
+    pub macrolanguage_fallback: MacrolanguageFallback,
+    pub equivalences: Vec<Vec<Language>>,
+    pub paradigm_locales: Vec<LanguageIdentifier>,
+    pub region_priority: Vec<(Language, Vec<Region>)>,
+    pub sticky_locale: Option<LanguageIdentifier>,
+    pub bare_language_matches_all_regions: Option<bool>,
+    pub fallback_script: Option<Script>,
+    pub assume_language: Option<Language>,
+    pub max_comparisons: Option<usize>,
+    pub excluded_locales: Vec<LanguageIdentifier>,
+    pub script_fallbacks: Vec<(Language, Script, Script)>,
+    pub include_unmatched: bool,
+    pub available_weights: HashMap<String, f32>,
+    pub available_preference: AvailablePreference,
+}
+
+impl Default for NegotiatorConfig {
+    fn default() -> Self {
+        let mut config = Negotiator::new(NegotiationStrategy::Filtering).to_config();
+        config.default_behavior = None;
+        config
+    }
+}
+
+impl Negotiator {
+    /// Creates a negotiator with the given settings.
+    #[must_use]
+    pub fn from_config(config: NegotiatorConfig) -> Self {
+        Self {
+            strategy: config.strategy,
+            default_behavior: config
+                .default_behavior
+                .unwrap_or_else(|| config.strategy.into()),
+            default_position: config.default_position,
+            min_acceptable_level: config.min_acceptable_level,
+            consume_matched: config.consume_matched,
+            macrolanguage_fallback: config.macrolanguage_fallback,
+            equivalences: config.equivalences,
+            paradigm_locales: config.paradigm_locales,
+            region_priority: config.region_priority,
+            sticky_locale: config.sticky_locale,
+            bare_language_matches_all_regions: config.bare_language_matches_all_regions,
+            fallback_script: config.fallback_script,
+            assume_language: config.assume_language,
+            max_comparisons: config.max_comparisons,
+            excluded_locales: config.excluded_locales,
+            script_fallbacks: config.script_fallbacks,
+            include_unmatched: config.include_unmatched,
+            available_weights: normalize_weights(config.available_weights),
+            available_preference: config.available_preference,
+        }
+    }
+
+    /// Returns the settings of the negotiator, which recreate it with
+    /// [`Negotiator::from_config`].
+    #[must_use]
+    pub fn to_config(&self) -> NegotiatorConfig {
+        NegotiatorConfig {
+            strategy: self.strategy,
+            default_behavior: Some(self.default_behavior),
+            default_position: self.default_position,
+            min_acceptable_level: self.min_acceptable_level,
+            consume_matched: self.consume_matched,
+            macrolanguage_fallback: self.macrolanguage_fallback,
+            equivalences: self.equivalences.clone(),
+            paradigm_locales: self.paradigm_locales.clone(),
+            region_priority: self.region_priority.clone(),
+            sticky_locale: self.sticky_locale.clone(),
+            bare_language_matches_all_regions: self.bare_language_matches_all_regions,
+            fallback_script: self.fallback_script,
+            assume_language: self.assume_language,
+            max_comparisons: self.max_comparisons,
+            excluded_locales: self.excluded_locales.clone(),
+            script_fallbacks: self.script_fallbacks.clone(),
+            include_unmatched: self.include_unmatched,
+            available_weights: self.available_weights.clone(),
+            available_preference: self.available_preference,
+        }
+    }
+}
@@ -42,8 +42,17 @@ impl LocaleExpander {
             b if *b == &langid!("sr") => langid!("sr-Cyrl-SR"),
             b if *b == &langid!("sr-RU") => langid!("sr-Latn-SR"),
             b if *b == &langid!("az-IR") => langid!("az-Arab-IR"),
+            b if *b == &langid!("zh") => langid!("zh-Hans-CN"),
+            b if *b == &langid!("zh-Hans") => langid!("zh-Hans-CN"),
+            b if *b == &langid!("zh-Hant") => langid!("zh-Hant-TW"),
             b if *b == &langid!("zh-GB") => langid!("zh-Hant-GB"),
             b if *b == &langid!("zh-US") => langid!("zh-Hant-US"),
+            b if *b == &langid!("zh-TW") => langid!("zh-Hant-TW"),
+            b if *b == &langid!("zh-HK") => langid!("zh-Hant-HK"),
+            b if *b == &langid!("zh-MO") => langid!("zh-Hant-MO"),
+            b if *b == &langid!("zh-CN") => langid!("zh-Hans-CN"),
+            b if *b == &langid!("zh-SG") => langid!("zh-Hans-SG"),
+            b if *b == &langid!("yue") => langid!("yue-Hant-HK"),
             _ => {
                 let lang = &input.language;
 
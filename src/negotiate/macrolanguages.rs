@@ -0,0 +1,102 @@
+use icu_locid::subtags::{language, Language};
+
+/// Individual languages and the ISO 639-3 macrolanguages encompassing them.
+static MACROLANGUAGES: &[(Language, Language)] = &[
+    (language!("aae"), language!("sq")),
+    (language!("aao"), language!("ar")),
+    (language!("aat"), language!("sq")),
+    (language!("abh"), language!("ar")),
+    (language!("abv"), language!("ar")),
+    (language!("acm"), language!("ar")),
+    (language!("acq"), language!("ar")),
+    (language!("acw"), language!("ar")),
+    (language!("acx"), language!("ar")),
+    (language!("acy"), language!("ar")),
+    (language!("adf"), language!("ar")),
+    (language!("aeb"), language!("ar")),
+    (language!("aec"), language!("ar")),
+    (language!("afb"), language!("ar")),
+    (language!("ajp"), language!("ar")),
+    (language!("aln"), language!("sq")),
+    (language!("als"), language!("sq")),
+    (language!("apc"), language!("ar")),
+    (language!("apd"), language!("ar")),
+    (language!("arb"), language!("ar")),
+    (language!("arq"), language!("ar")),
+    (language!("ars"), language!("ar")),
+    (language!("ary"), language!("ar")),
+    (language!("arz"), language!("ar")),
+    (language!("auz"), language!("ar")),
+    (language!("avl"), language!("ar")),
+    (language!("ayh"), language!("ar")),
+    (language!("ayl"), language!("ar")),
+    (language!("ayn"), language!("ar")),
+    (language!("ayp"), language!("ar")),
+    (language!("azb"), language!("az")),
+    (language!("azj"), language!("az")),
+    (language!("bbz"), language!("ar")),
+    (language!("cdo"), language!("zh")),
+    (language!("cjy"), language!("zh")),
+    (language!("ckb"), language!("ku")),
+    (language!("cmn"), language!("zh")),
+    (language!("cpx"), language!("zh")),
+    (language!("czh"), language!("zh")),
+    (language!("czo"), language!("zh")),
+    (language!("ekk"), language!("et")),
+    (language!("gan"), language!("zh")),
+    (language!("hak"), language!("zh")),
+    (language!("hsn"), language!("zh")),
+    (language!("khk"), language!("mn")),
+    (language!("kmr"), language!("ku")),
+    (language!("ltg"), language!("lv")),
+    (language!("lvs"), language!("lv")),
+    (language!("lzh"), language!("zh")),
+    (language!("mnp"), language!("zh")),
+    (language!("mvf"), language!("mn")),
+    (language!("nan"), language!("zh")),
+    (language!("nb"), language!("no")),
+    (language!("nn"), language!("no")),
+    (language!("pbt"), language!("ps")),
+    (language!("pbu"), language!("ps")),
+    (language!("pes"), language!("fa")),
+    (language!("pga"), language!("ar")),
+    (language!("prs"), language!("fa")),
+    (language!("pst"), language!("ps")),
+    (language!("sdh"), language!("ku")),
+    (language!("shu"), language!("ar")),
+    (language!("ssh"), language!("ar")),
+    (language!("swc"), language!("sw")),
+    (language!("swh"), language!("sw")),
+    (language!("uzn"), language!("uz")),
+    (language!("uzs"), language!("uz")),
+    (language!("vro"), language!("et")),
+    (language!("wuu"), language!("zh")),
+    (language!("yue"), language!("zh")),
+    (language!("zsm"), language!("ms")),
+];
+
+/// Returns the macrolanguage encompassing the language, if any.
+pub fn macrolanguage(lang: Language) -> Option<Language> {
+    MACROLANGUAGES
+        .binary_search_by(|(l, _)| l.cmp(&lang))
+        .ok()
+        .map(|idx| MACROLANGUAGES[idx].1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_macrolanguages_sort() {
+        for v in MACROLANGUAGES.windows(2) {
+            let (v1, v2) = (v[0], v[1]);
+            assert!(
+                v1.0 < v2.0,
+                "Language \"{}\" is placed after \"{}\"",
+                v1.0,
+                v2.0
+            );
+        }
+    }
+}
@@ -32,7 +32,7 @@
 //!            expected.iter().map(|t| t.as_ref()).collect::<Vec<&LanguageIdentifier>>());
 //! ```
 //!
-//! # The exact algorithm is custom, and consists of a 6 level strategy:
+//! # The exact algorithm is custom, and consists of an 8 level strategy:
 //!
 //! ### 1) Attempt to find an exact match for each requested locale in available locales.
 //!
@@ -56,6 +56,36 @@
 //!                |-- becomes "en-*-*-*"
 //! ```
 //!
+//! With the `Filtering` strategy, available locales matched at the same step
+//! are ordered from the most to the least specific, then by the weights given
+//! to [`Negotiator::with_available_weights`], and otherwise keep their available
+//! order, unless it is reversed by [`Negotiator::with_available_preference`].
+//! The `Lookup` and `Matching` strategies pick the first of them, only taking
+//! the weights into account:
+//!
+//! ```text
+//! // [requested] * [available] = [supported]
+//!
+//! ["en-Latn-US"] * ["en", "en-US"] = ["en-US", "en"] // Filtering
+//! ["en-Latn-US"] * ["en", "en-US"] = ["en"] // Lookup
+//! ```
+//!
+//! If the requested or available locales use a deprecated region code, like
+//! `CS` for Serbia and Montenegro, steps 1 and 2 are repeated with the regions
+//! replaced by their successors, as step 2b. When a region has multiple
+//! successors, the likely region of the language is used if it is one of them,
+//! and the first successor otherwise. Likewise, a numeric UN M.49 code of a
+//! country, like `840`, is replaced by its alpha-2 code, while codes of groups
+//! of countries, like `419`, are kept.
+//!
+//! ```text
+//! // [requested] * [available] = [supported]
+//!
+//! ["sr-CS"] * ["sr-RS", "sr-ME"] = ["sr-RS"]
+//! ["en-840"] * ["en-GB", "en-US"] = ["en-US"]
+//! ["sr-RS"] * ["sr-ME", "sr-YU"] = ["sr-YU"]
+//! ```
+//!
 //! ### 3) Maximize the requested locale to find the best match in available locales.
 //!
 //! This part uses ICU's likelySubtags or similar database.
@@ -118,126 +148,2614 @@
 //!       |----- replace region with range: "en-*"
 //! ```
 //!
+//! If a fallback script is declared for the script of the requested locale with
+//! [`Negotiator::with_script_fallback`], this step is repeated with the script
+//! replaced by the fallback.
+//!
+//! ```text
+//! // [requested] * [available] = [supported]
+//!
+//! ["mn-Mong"] * ["mn-Cyrl"] = ["mn-Cyrl"] // with "Mong" falling back to "Cyrl" for "mn"
+//! ```
+//!
+//! ### 7) Attempt to look up for a language declared as equivalent.
+//!
+//! Equivalent languages are declared with [`Negotiator::with_equivalences`].
+//!
+//! Example:
+//!
+//! ```text
+//! // [requested] * [available] = [supported]
+//!
+//! ["nb"] * ["nn"] = ["nn"] // with ["nb", "nn", "no"] declared as equivalent
+//! ```
+//!
+//! ### 8) Optionally, attempt to look up for the macrolanguage of the requested locale.
+//!
+//! This step is disabled by default, see [`Negotiator::with_macrolanguage_fallback`].
+//!
+//! Example:
+//!
+//! ```text
+//! // [requested] * [available] = [supported]
+//!
+//! ["yue"] * ["zh-Hans", "zh-Hant"] = ["zh-Hant"]
+//!   ^^^
+//!     |----- maximize to "yue-Hant-HK", strip region and replace language: "zh-Hant-*"
+//! ```
+//!
+//! # Without fuzzy steps
+//!
+//! Steps 3 to 8 are extensions of this crate, which let a request like `en-AU`
+//! match `en-GB` when nothing closer is available. To only accept available
+//! locales equal to the requested ones or covering them as ranges, limit the
+//! negotiation to steps 1 and 2 with [`Negotiator::with_min_acceptable_level`]:
+//!
+//! ```
+//! use fluent_langneg::{convert_vec_str_to_langids_lossy, MatchKind, Negotiator, NegotiationStrategy};
+//! use icu_locid::langid;
+//!
+//! let requested = convert_vec_str_to_langids_lossy(&["en-AU", "de-DE", "fr"]);
+//! let available = convert_vec_str_to_langids_lossy(&["en-GB", "de", "fr-FR"]);
+//!
+//! let negotiator = Negotiator::new(NegotiationStrategy::Filtering)
+//!     .with_min_acceptable_level(MatchKind::AvailableRange);
+//! assert_eq!(
+//!     negotiator.negotiate(&requested, &available, None),
+//!     &[&langid!("de")]
+//! );
+//! ```
+//!
+//! This is not the extended filtering of RFC4647, where the requested locale
+//! is the range, since `fr` doesn't match `fr-FR` here. See [`filter_available`]
+//! for it. Matches at step 2b, with deprecated regions replaced, are discarded
+//! as well, unless the level is [`MatchKind::RegionAlias`].
+//!
+//! # Scripts
+//!
+//! A missing script is treated as a range, but locales with different explicit
+//! scripts never match, at any step, unless a fallback between them is declared
+//! with [`Negotiator::with_script_fallback`]:
+//!
+//! ```text
+//! // [requested] * [available] = [supported]
+//!
+//! ["sr-Cyrl-RS"] * ["sr-Latn-RS", "sr"] = ["sr"]
+//! ```
+//!
+//! The script of a requested locale without one is inferred from likely
+//! subtags in step 3, often from its region, and is then kept in the following
+//! steps:
+//!
+//! ```text
+//! // [requested] * [available] = [supported]
+//!
+//! ["zh-TW"] * ["zh-Hans", "zh-Hant"] = ["zh-Hant"] // step 3, "zh-Hant-TW"
+//! ["zh-TW"] * ["zh-Hans-TW"] = []
+//! ```
+//!
+//! # Variants
+//!
+//! Variants are sorted alphabetically when a locale is parsed, following CLDR
+//! rather than the registration order suggested by BCP 47. They are therefore
+//! compared regardless of their order, so `de-1996-1901` is equal to
+//! `de-1901-1996`. The original order is not kept by [`LanguageIdentifier`],
+//! so there is no order-sensitive mode. When a locale is treated as a range,
+//! its variants match if they are a subset of the variants of the other locale:
+//!
+//! ```text
+//! // [requested] * [available] = [supported]
+//!
+//! ["de-1901-1996"] * ["de-1901"] = ["de-1901"] // step 2
+//! ["de-1901"] * ["de-1901-1996"] = ["de-1901-1996"] // step 4
+//! ```
+//!
+//! At step 4, available locales with all of the requested variants come before
+//! ones with other variants:
+//!
+//! ```text
+//! // [requested] * [available] = [supported]
+//!
+//! ["sl-rozaj-biske"] * ["sl-rozaj-njiva", "sl-rozaj-biske-1994"] = ["sl-rozaj-biske-1994", "sl-rozaj-njiva"]
+//! ```
+//!
+//! The `posix` variant marks machine-readable formatting, so it is never covered
+//! by a range. A request for it falls back to the locale without the variant at
+//! step 4, but other requests never match it:
+//!
+//! ```text
+//! // [requested] * [available] = [supported]
+//!
+//! ["en-US-posix"] * ["en-US"] = ["en-US"] // step 4
+//! ["en-US"] * ["en-US-posix"] = []
+//! ```
+//!
+//! # Undetermined language
+//!
+//! An available locale with the undetermined language, like `und-Cyrl`, is
+//! treated as a range from step 2 onwards, so it matches any language with
+//! compatible script and region, like the ranges of [`crate::langid::parse_range`].
+//! A requested `und`, on the other hand, only matches an available `und`:
+//!
+//! ```text
+//! // [requested] * [available] = [supported]
+//!
+//! ["sr-Cyrl-RS"] * ["und-Latn", "und-Cyrl"] = ["und-Cyrl"] // step 2
+//! ["und"] * ["de", "und"] = ["und"]
+//! ```
+//!
+//! A bare available `und` is the root locale, which CLDR uses as the ultimate
+//! fallback, and which is parsed from the tag `root` as well. It doesn't match
+//! other requested locales, but is used as the default locale when none is
+//! given, according to the [`DefaultBehavior`]:
+//!
+//! ```text
+//! // [requested] * [available] = [supported]
+//!
+//! ["de-CH", "fr"] * ["und", "fr", "de-AT"] = ["de-AT", "fr", "und"] // filtering
+//! ["pl"] * ["und", "fr"] = ["und"] // lookup
+//! ```
+//!
+//! With [`Negotiator::with_assume_language`], a requested locale without a
+//! language continues from step 3 with the likely language of its region, or
+//! with the assumed language.
+//!
+//! # Consumed available locales
+//!
+//! Each available locale is returned at most once. Once an available locale
+//! has been matched, it is removed from the list the following requested
+//! locales are matched against, so with `Matching` and `Lookup` strategies
+//! a later requested locale finds its best match among the remaining locales:
+//!
+//! ```text
+//! // [requested] * [available] = [supported]
+//!
+//! ["en-US", "en-GB"] * ["en-GB", "en-AU"] = ["en-GB", "en-AU"] // Matching
+//! ```
+//!
+//! This can be changed with [`Negotiator::with_consume_matched`].
+//!
+//! # Requested locales as ranges
+//!
+//! Available locales are treated as ranges from step 2 onwards, while requested
+//! locales are treated as ranges only in steps 4 and 6, after their variants and
+//! region have been replaced with a range.
+//!
+//! In result, a bare language requested by the user, like `en` coming from an
+//! `Accept-Language` header, is matched against `en-US` even if there is no
+//! likely subtags data for it:
+//!
+//! ```text
+//! // [requested] * [available] = [supported]
+//!
+//! ["ja"] * ["ja-JP"] = ["ja-JP"]
+//!   ^^       ^^^^^
+//!    |           |-- becomes "ja-*-JP-*"
+//!    |
+//!    |----- step 4 treats it as a range: "ja-*-*-*"
+//! ```
+//!
+//! This can be made explicit with
+//! [`Negotiator::with_bare_language_matches_all_regions`]. When enabled, a
+//! requested locale without a region matches any region already in step 2.
+//! When disabled, it only matches available locales without a region, or in
+//! its likely region at step 3:
+//!
+//! ```text
+//! // [requested] * [available] = [supported]
+//!
+//! ["en"] * ["en-GB", "en-US"] = ["en-GB", "en-US"] // enabled, step 2
+//! ["en"] * ["en-GB", "en-US"] = ["en-US"] // disabled, step 3
+//! ```
+//!
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display};
 
-use icu_locid::LanguageIdentifier;
+use crate::langid::parse_tag;
+use icu_locid::{
+    subtags::{variant, Language, Region, Script, Variant, Variants},
+    LanguageIdentifier,
+};
 
+mod config;
 #[cfg(not(feature = "cldr"))]
 mod likely_subtags;
+mod macrolanguages;
+pub(crate) mod region_aliases;
+pub use config::NegotiatorConfig;
 #[cfg(feature = "cldr")]
-use icu_locid_transform::{LocaleExpander, TransformResult};
+pub(crate) use icu_locid_transform::{LocaleExpander, TransformResult};
 #[cfg(not(feature = "cldr"))]
-use likely_subtags::{LocaleExpander, TransformResult};
+pub(crate) use likely_subtags::{LocaleExpander, TransformResult};
+
+/// Returns an identifier of the locale data used by the negotiation.
+///
+/// Region aliases and macrolanguages are bundled with this crate, so the
+/// identifier starts with its version. With the `cldr` feature, likely subtags
+/// come from `icu_locid_transform`, which is marked by a `+cldr` suffix, and
+/// their exact version is the one resolved for that dependency.
+///
+/// Services which have to negotiate consistently can compare this value
+/// to verify that they use the same data.
+#[must_use]
+pub fn data_version() -> &'static str {
+    #[cfg(feature = "cldr")]
+    return concat!(env!("CARGO_PKG_VERSION"), "+cldr");
+    #[cfg(not(feature = "cldr"))]
+    return env!("CARGO_PKG_VERSION");
+}
 
 #[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
 pub enum NegotiationStrategy {
+    /// Returns every available locale matching the requested ones.
+    ///
+    /// Locales matched at the same step are ordered from the most to the least
+    /// specific, so the first of them can differ from the one picked by
+    /// `Lookup` and `Matching`, which keep the available order. This also
+    /// applies to the functions negotiating with this strategy, like
+    /// [`negotiate_map`], [`negotiate_best_within`] and [`explain`].
     Filtering,
     Matching,
     Lookup,
 }
 
-fn subtag_matches<P: PartialEq>(
-    subtag1: &Option<P>,
-    subtag2: &Option<P>,
-    as_range1: bool,
-    as_range2: bool,
-) -> bool {
-    (as_range1 && subtag1.is_none()) || (as_range2 && subtag2.is_none()) || subtag1 == subtag2
+/// Which sides of a comparison are treated as ranges, whose missing subtags
+/// match any value.
+///
+/// "Self" is the first identifier passed to [`matches_mode`], which is the
+/// available locale during the negotiation, and "other" is the second one.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum RangeMode {
+    /// Both identifiers must be equal.
+    Exact,
+    /// The first identifier is treated as a range.
+    SelfIsRange,
+    /// The second identifier is treated as a range.
+    OtherIsRange,
+    /// Both identifiers are treated as ranges.
+    BothRanges,
 }
 
+impl RangeMode {
+    const fn self_is_range(self) -> bool {
+        matches!(self, Self::SelfIsRange | Self::BothRanges)
+    }
+
+    const fn other_is_range(self) -> bool {
+        matches!(self, Self::OtherIsRange | Self::BothRanges)
+    }
+}
+
+fn subtag_matches<P: PartialEq>(subtag1: &Option<P>, subtag2: &Option<P>, mode: RangeMode) -> bool {
+    (mode.self_is_range() && subtag1.is_none())
+        || (mode.other_is_range() && subtag2.is_none())
+        || subtag1 == subtag2
+}
+
+/// Normalizes the tags of weights, so that they can be looked up by the string
+/// form of available locales.
+fn normalize_weights(weights: HashMap<String, f32>) -> HashMap<String, f32> {
+    weights
+        .into_iter()
+        .map(|(tag, weight)| match parse_tag(tag.as_bytes()) {
+            Ok(langid) => (langid.to_string(), weight),
+            Err(_) => (tag, weight),
+        })
+        .collect()
+}
+
+fn specificity(langid: &LanguageIdentifier) -> usize {
+    usize::from(!langid.language.is_empty())
+        + usize::from(langid.script.is_some())
+        + usize::from(langid.region.is_some())
+        + langid.variants.len()
+}
+
+const POSIX: Variant = variant!("posix");
+
+/// Returns whether the identifier is the root locale, `und`, which CLDR uses
+/// as the ultimate fallback.
+fn is_root(langid: &LanguageIdentifier) -> bool {
+    *langid == LanguageIdentifier::UND
+}
+
+#[inline(always)]
+fn is_language_only(langid: &LanguageIdentifier) -> bool {
+    langid.script.is_none() && langid.region.is_none() && langid.variants.is_empty()
+}
+
+/// Returns whether two identifiers match, treating missing subtags of a side
+/// used as a range as matching any value.
+///
+/// The undetermined language, `und`, is stored as an empty language, so it
+/// matches any language on a side used as a range, but only `und` otherwise.
 #[inline(always)]
-fn matches(
-    lid1: &LanguageIdentifier,
-    lid2: &LanguageIdentifier,
-    range1: bool,
-    range2: bool,
-) -> bool {
-    ((range1 && lid1.language.is_empty())
-        || (range2 && lid2.language.is_empty())
-        || lid1.language == lid2.language)
-        && subtag_matches(&lid1.script, &lid2.script, range1, range2)
-        && subtag_matches(&lid1.region, &lid2.region, range1, range2)
-        && ((range1 && lid1.variants.is_empty())
-            || (range2 && lid2.variants.is_empty())
-            || lid1.variants == lid2.variants)
+fn matches_mode(lid1: &LanguageIdentifier, lid2: &LanguageIdentifier, mode: RangeMode) -> bool {
+    // Most available locales have a different language, so bail out before
+    // comparing the other subtags.
+    let language_matches = lid1.language == lid2.language
+        || (mode.self_is_range() && lid1.language.is_empty())
+        || (mode.other_is_range() && lid2.language.is_empty());
+    if !language_matches {
+        return false;
+    }
+
+    // The most common range is a bare language, like `en`, which matches any
+    // other subtags except the POSIX variant, so skip comparing them.
+    if (mode.self_is_range() && is_language_only(lid1) && !lid2.variants.contains(&POSIX))
+        || (mode.other_is_range() && is_language_only(lid2) && !lid1.variants.contains(&POSIX))
+    {
+        return true;
+    }
+
+    subtag_matches(&lid1.script, &lid2.script, mode)
+        && subtag_matches(&lid1.region, &lid2.region, mode)
+        && variants_match(&lid1.variants, &lid2.variants, mode)
+}
+
+/// Returns whether the language tag falls under the range, like `en-US` under `en`.
+///
+/// Subtags missing from the range match any value, and `und` matches any language.
+/// The range may contain wildcards, like `en-*-US`, see [`crate::langid::parse_range`].
+/// Returns `false` if either of them fails to parse.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::matches_range;
+///
+/// assert!(matches_range("en-US", "en"));
+/// assert!(!matches_range("en", "en-US"));
+/// assert!(matches_range("sr-Cyrl-RS", "und-RS"));
+/// assert!(matches_range("sr-Cyrl-RS", "*-RS"));
+/// ```
+#[must_use]
+pub fn matches_range(tag: &str, range: &str) -> bool {
+    match (parse_tag(tag.as_bytes()), crate::langid::parse_range(range)) {
+        (Ok(tag), Ok(range)) => matches_mode(&tag, &range, RangeMode::OtherIsRange),
+        _ => false,
+    }
+}
+
+/// Returns every available tag which falls under the range, in their original
+/// order, following the extended filtering of
+/// [RFC4647](https://www.ietf.org/rfc/rfc4647.txt).
+///
+/// Unlike the negotiation, no likely subtags or other fallbacks are used, and
+/// the result is not sorted by the quality of the match. The range may contain
+/// wildcards, like `en-*-US`, see [`crate::langid::parse_range`]. Tags which
+/// fail to parse are skipped, and an invalid range matches nothing.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::filter_available;
+///
+/// let available = ["en", "en-GB", "de-DE", "en-Latn-US", "en-US"];
+/// assert_eq!(filter_available("en", &available), &["en", "en-GB", "en-Latn-US", "en-US"]);
+/// assert_eq!(filter_available("en-*-US", &available), &["en-Latn-US", "en-US"]);
+/// assert_eq!(filter_available("*-DE", &available), &["de-DE"]);
+/// ```
+#[must_use]
+pub fn filter_available<'a>(range: &str, available: &[&'a str]) -> Vec<&'a str> {
+    let Ok(range) = crate::langid::parse_range(range) else {
+        return vec![];
+    };
+    available
+        .iter()
+        .copied()
+        .filter(|tag| {
+            parse_tag(tag.as_bytes())
+                .is_ok_and(|tag| matches_mode(&tag, &range, RangeMode::OtherIsRange))
+        })
+        .collect()
+}
+
+/// Variants of a range match if they are a subset of the other side's variants.
+///
+/// Variants are kept sorted by `LanguageIdentifier`, so their order is not significant.
+fn variants_match(variants1: &Variants, variants2: &Variants, mode: RangeMode) -> bool {
+    if variants1.contains(&POSIX) != variants2.contains(&POSIX) {
+        return false;
+    }
+    (mode.self_is_range() && variants1.iter().all(|v| variants2.contains(v)))
+        || (mode.other_is_range() && variants2.iter().all(|v| variants1.contains(v)))
+        || variants1 == variants2
+}
+
+/// The step of the negotiation at which an available locale has been matched.
+///
+/// The variants are ordered from the best to the worst match.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum MatchKind {
+    /// 1) The available locale is equal to the requested one.
+    Exact,
+    /// 2) The available locale, treated as a range, matches the requested one.
+    AvailableRange,
+    /// 2b) The available locale matches the requested one once deprecated and
+    /// numeric regions are replaced with their successors.
+    RegionAlias,
+    /// 3) The available locale matches the maximized requested locale.
+    LikelySubtags,
+    /// 4) The available locale matches the requested locale with variants replaced with a range.
+    VariantRange,
+    /// 5) The available locale matches the maximized requested locale stripped of its region.
+    LikelySubtagsWithoutRegion,
+    /// 6) The available locale matches the requested locale with region replaced with a range.
+    RegionRange,
+    /// 6b) The available locale matches the requested locale with its script
+    /// replaced by a user-defined fallback.
+    ///
+    /// See [`Negotiator::with_script_fallback`].
+    ScriptFallback,
+    /// 7) The available locale matches the requested locale through a user-defined equivalence.
+    ///
+    /// See [`Negotiator::with_equivalences`].
+    Equivalent,
+    /// 8) The available locale matches the requested locale through its macrolanguage.
+    ///
+    /// See [`Negotiator::with_macrolanguage_fallback`].
+    Macrolanguage,
 }
 
+impl Display for MatchKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Exact => "exact",
+            Self::AvailableRange => "available-range",
+            Self::RegionAlias => "region-alias",
+            Self::LikelySubtags => "likely-subtags",
+            Self::VariantRange => "variant-range",
+            Self::LikelySubtagsWithoutRegion => "likely-subtags-without-region",
+            Self::RegionRange => "region-range",
+            Self::ScriptFallback => "script-fallback",
+            Self::Equivalent => "equivalent",
+            Self::Macrolanguage => "macrolanguage",
+        })
+    }
+}
+
+/// A summary of how well the requested locales have been matched.
+///
+/// The variants are ordered from the most to the least confident.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub enum Confidence {
+    /// Every matched requested locale has an exact match.
+    Exact,
+    /// Every matched requested locale has a match at step 3 or better.
+    Good,
+    /// A matched requested locale only has a match at step 4 or worse, like
+    /// one in a different region.
+    Fuzzy,
+    /// No requested locale has been matched, so at most the default locale is used.
+    DefaultOnly,
+}
+
+impl Confidence {
+    fn from_matches<A>(matches: &[Match<A>]) -> Self {
+        let mut best: Vec<(usize, MatchKind)> = vec![];
+        for m in matches {
+            match best
+                .iter_mut()
+                .find(|(requested, _)| *requested == m.requested)
+            {
+                Some((_, kind)) => *kind = (*kind).min(m.kind),
+                None => best.push((m.requested, m.kind)),
+            }
+        }
+        match best.iter().map(|(_, kind)| *kind).max() {
+            None => Self::DefaultOnly,
+            Some(MatchKind::Exact) => Self::Exact,
+            Some(kind) if kind <= MatchKind::LikelySubtags => Self::Good,
+            Some(_) => Self::Fuzzy,
+        }
+    }
+}
+
+/// An entry of the result of [`Negotiator::negotiate_detailed`].
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Negotiated<'r, 'a, R, A> {
+    /// An available locale matched by the requested locale.
+    Matched(&'r R, &'a A),
+    /// A requested locale without a match, included with
+    /// [`Negotiator::with_include_unmatched`].
+    Unmatched(&'r R),
+    /// A default locale, appended according to the [`DefaultBehavior`].
+    Default(&'a A),
+}
+
+/// A locale of a [`NegotiationResult`].
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct MatchedLocale<'a, A> {
+    pub locale: &'a A,
+    /// The index of the requested locale which matched it, or `None` for a
+    /// default locale.
+    pub requested: Option<usize>,
+    /// The step at which it matched, or `None` for a default locale.
+    pub kind: Option<MatchKind>,
+}
+
+/// The result of [`Negotiator::negotiate_result`], describing how each
+/// negotiated locale has been found.
+///
+/// With the `serde` feature, it is serialized for tooling as the negotiated
+/// tags and whether a default locale has been added, like
+/// `{"supported":["fr","en-US"],"default_used":false}`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct NegotiationResult<'a, A> {
+    /// The negotiated locales, in the order of [`Negotiator::negotiate`].
+    pub matches: Vec<MatchedLocale<'a, A>>,
+}
+
+impl<'a, A> NegotiationResult<'a, A> {
+    /// Returns the first negotiated locale, if any.
+    #[must_use]
+    pub fn best(&self) -> Option<&'a A> {
+        self.matches.first().map(|m| m.locale)
+    }
+
+    /// Returns the negotiated locales, as returned by [`Negotiator::negotiate`].
+    #[must_use]
+    pub fn tags(&self) -> Vec<&'a A> {
+        self.matches.iter().map(|m| m.locale).collect()
+    }
+
+    /// Returns whether a default locale has been added to the result.
+    ///
+    /// A default locale which has been matched by a requested locale is not
+    /// counted as added.
+    #[must_use]
+    pub fn used_default(&self) -> bool {
+        self.matches.iter().any(|m| m.requested.is_none())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<A: Display> serde::Serialize for NegotiationResult<'_, A> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        struct Tags<'r, 'a, A>(&'r [MatchedLocale<'a, A>]);
+
+        impl<A: Display> serde::Serialize for Tags<'_, '_, A> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq(self.0.iter().map(|m| m.locale.to_string()))
+            }
+        }
+
+        let mut result = serializer.serialize_struct("NegotiationResult", 2)?;
+        result.serialize_field("supported", &Tags(&self.matches))?;
+        result.serialize_field("default_used", &self.used_default())?;
+        result.end()
+    }
+}
+
+/// Controls whether languages can fall back on their ISO 639-3 macrolanguage.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum MacrolanguageFallback {
+    /// Languages only match themselves.
+    #[default]
+    Disabled,
+    /// An individual language, like `yue`, matches its macrolanguage, like `zh`.
+    ToMacrolanguage,
+    /// Additionally, a macrolanguage matches the individual languages it encompasses.
+    Bidirectional,
+}
+
+struct Match<'a, A> {
+    locale: &'a A,
+    requested: usize,
+    kind: MatchKind,
+}
+
+/// Returns the available locales matching the requested ones, without the default locale.
+///
+/// The result is ordered by the requested locales they matched, and for each
+/// requested locale, by the step at which they matched. Locales matched at the
+/// same step are ordered from the most to the least specific, and otherwise
+/// keep the order of the available list.
+#[must_use]
 pub fn filter_matches<'a, R: 'a + AsRef<LanguageIdentifier>, A: 'a + AsRef<LanguageIdentifier>>(
     requested: &[R],
     available: &'a [A],
     strategy: NegotiationStrategy,
 ) -> Vec<&'a A> {
-    let mut lc: Option<LocaleExpander> = None;
+    Negotiator::new(strategy).filter_matches(requested, available)
+}
 
-    let mut supported_locales = vec![];
+/// Returns the best available locale for each requested locale.
+///
+/// See [`Negotiator::negotiate_map`].
+#[must_use]
+pub fn negotiate_map<
+    'a,
+    'r,
+    R: 'r + AsRef<LanguageIdentifier>,
+    A: 'a + AsRef<LanguageIdentifier>,
+>(
+    requested: &'r [R],
+    available: &'a [A],
+    strategy: NegotiationStrategy,
+) -> Vec<(&'r R, Option<&'a A>)> {
+    Negotiator::new(strategy).negotiate_map(requested, available)
+}
 
-    let mut available_locales: Vec<&A> = available.iter().collect();
+/// Returns the step at which the available locale matches the requested one,
+/// or `None` if it doesn't match.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::{match_level, MatchKind};
+/// use icu_locid::langid;
+///
+/// assert_eq!(match_level(&langid!("en-US"), &langid!("en")), Some(MatchKind::AvailableRange));
+/// assert_eq!(match_level(&langid!("en-US"), &langid!("en-GB")), Some(MatchKind::RegionRange));
+/// assert_eq!(match_level(&langid!("en-US"), &langid!("de")), None);
+/// ```
+#[must_use]
+pub fn match_level<R: AsRef<LanguageIdentifier>, A: AsRef<LanguageIdentifier>>(
+    requested: &R,
+    available: &A,
+) -> Option<MatchKind> {
+    Negotiator::new(NegotiationStrategy::Lookup).match_level(requested, available)
+}
 
-    macro_rules! test_strategy {
-        ($req:ident, $self_as_range:expr, $other_as_range:expr) => {{
-            let mut match_found = false;
-            available_locales.retain(|locale| {
-                if strategy != NegotiationStrategy::Filtering && match_found {
-                    return true;
-                }
+/// Returns up to `n` best available locales matching the requested ones.
+///
+/// See [`Negotiator::negotiate_top_n`].
+#[must_use]
+pub fn negotiate_top_n<'a, R: 'a + AsRef<LanguageIdentifier>, A: 'a + AsRef<LanguageIdentifier>>(
+    requested: &[R],
+    available: &'a [A],
+    n: usize,
+    strategy: NegotiationStrategy,
+) -> Vec<&'a A> {
+    Negotiator::new(strategy).negotiate_top_n(requested, available, n)
+}
+
+/// Returns every available locale matching the requested ones, ranked from the
+/// best to the worst, with the step at which each has been matched.
+///
+/// The first locale is the one the `Lookup` strategy would return. See
+/// [`Negotiator::negotiate_ranked`].
+#[must_use]
+pub fn negotiate_ranked<
+    'a,
+    R: 'a + AsRef<LanguageIdentifier>,
+    A: 'a + AsRef<LanguageIdentifier>,
+>(
+    requested: &[R],
+    available: &'a [A],
+) -> Vec<(&'a A, MatchKind)> {
+    Negotiator::new(NegotiationStrategy::Lookup).negotiate_ranked(requested, available)
+}
+
+/// Returns the available locales matching the requested ones, grouped by the
+/// step at which they have been matched.
+///
+/// See [`Negotiator::negotiate_grouped`].
+#[must_use]
+pub fn negotiate_grouped<
+    'a,
+    R: 'a + AsRef<LanguageIdentifier>,
+    A: 'a + AsRef<LanguageIdentifier>,
+>(
+    requested: &[R],
+    available: &'a [A],
+    strategy: NegotiationStrategy,
+) -> BTreeMap<MatchKind, Vec<&'a A>> {
+    Negotiator::new(strategy).negotiate_grouped(requested, available)
+}
+
+/// Returns the single best negotiated locale, or the default locale.
+///
+/// See [`Negotiator::negotiate_one`].
+#[must_use]
+pub fn negotiate_one<
+    'a,
+    R: 'a + AsRef<LanguageIdentifier>,
+    A: 'a + AsRef<LanguageIdentifier> + PartialEq,
+>(
+    requested: &[R],
+    available: &'a [A],
+    default: Option<&'a A>,
+    strategy: NegotiationStrategy,
+) -> Option<&'a A> {
+    Negotiator::new(strategy).negotiate_one(requested, available, default)
+}
+
+/// Returns the requested locales which haven't been matched by any available locale.
+///
+/// See [`Negotiator::unserved`].
+#[must_use]
+pub fn unserved<'a, 'r, R: 'r + AsRef<LanguageIdentifier>, A: 'a + AsRef<LanguageIdentifier>>(
+    requested: &'r [R],
+    available: &'a [A],
+    strategy: NegotiationStrategy,
+) -> Vec<&'r R> {
+    Negotiator::new(strategy).unserved(requested, available)
+}
+
+/// Returns the single best available locale, if it has been matched at
+/// `max_level` or better.
+///
+/// Requested locales are tried in order, like with the `Lookup` strategy, so
+/// a close match for a less preferred locale doesn't override an acceptable
+/// match for a more preferred one. Returning `None` lets the caller show that
+/// no content is available instead of a poor match.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::{convert_vec_str_to_langids_lossy, negotiate_best_within, MatchKind};
+/// use icu_locid::langid;
+///
+/// let requested = convert_vec_str_to_langids_lossy(&["en-US"]);
+/// let available = convert_vec_str_to_langids_lossy(&["de", "en-GB"]);
+///
+/// assert_eq!(
+///     negotiate_best_within(&requested, &available, MatchKind::RegionRange),
+///     Some(&langid!("en-GB"))
+/// );
+/// assert_eq!(
+///     negotiate_best_within(&requested, &available, MatchKind::LikelySubtags),
+///     None
+/// );
+/// ```
+#[must_use]
+pub fn negotiate_best_within<
+    'a,
+    R: 'a + AsRef<LanguageIdentifier>,
+    A: 'a + AsRef<LanguageIdentifier>,
+>(
+    requested: &[R],
+    available: &'a [A],
+    max_level: MatchKind,
+) -> Option<&'a A> {
+    Negotiator::new(NegotiationStrategy::Lookup)
+        .with_min_acceptable_level(max_level)
+        .filter_matches(requested, available)
+        .into_iter()
+        .next()
+}
+
+/// Returns the fraction of `expected` locales, like the ones seen in analytics,
+/// which are matched by an available locale at `max_level` or better.
+///
+/// Each expected locale is matched independently, so a single available locale
+/// can serve many of them. An empty `expected` list is fully covered.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::{convert_vec_str_to_langids_lossy, coverage, MatchKind};
+///
+/// let expected = convert_vec_str_to_langids_lossy(&["en-US", "en-GB", "de", "pl"]);
+/// let available = convert_vec_str_to_langids_lossy(&["en-US", "de-DE"]);
+///
+/// assert_eq!(coverage(&expected, &available, MatchKind::Exact), 0.25);
+/// assert_eq!(coverage(&expected, &available, MatchKind::RegionRange), 0.75);
+/// ```
+#[must_use]
+pub fn coverage<R: AsRef<LanguageIdentifier>, A: AsRef<LanguageIdentifier>>(
+    expected: &[R],
+    available: &[A],
+    max_level: MatchKind,
+) -> f32 {
+    if expected.is_empty() {
+        return 1.0;
+    }
+    let unserved = Negotiator::new(NegotiationStrategy::Matching)
+        .with_consume_matched(false)
+        .with_min_acceptable_level(max_level)
+        .unserved(expected, available);
+    (expected.len() - unserved.len()) as f32 / expected.len() as f32
+}
+
+/// A redundant pair of available locales, reported by [`validate_available`].
+///
+/// The locales of a pair are in the order of the available list, except for
+/// [`AvailabilityWarning::Maximized`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AvailabilityWarning<'a, A> {
+    /// The locale is listed more than once.
+    Duplicate { first: &'a A, second: &'a A },
+    /// The locales are equal once deprecated codes are replaced, like `iw-IL`
+    /// and `he-IL`.
+    Alias { first: &'a A, second: &'a A },
+    /// The locale is listed along with its likely subtags added, like `zh-CN`
+    /// and `zh-Hans-CN`, so which one is negotiated depends on the step at
+    /// which they match.
+    Maximized { locale: &'a A, maximized: &'a A },
+}
+
+/// Returns the redundant pairs of available locales, so that the list can be
+/// cleaned up.
+///
+/// Locales with the undetermined language, like `und`, are not reported as
+/// maximized forms, as they are commonly used as catch-all fallbacks.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::{convert_vec_str_to_langids_lossy, validate_available, AvailabilityWarning};
+///
+/// let available = convert_vec_str_to_langids_lossy(&["zh-Hans-CN", "en-US", "zh-CN"]);
+///
+/// assert_eq!(
+///     validate_available(&available),
+///     &[AvailabilityWarning::Maximized {
+///         locale: &available[2],
+///         maximized: &available[0],
+///     }]
+/// );
+/// ```
+#[must_use]
+pub fn validate_available<A: AsRef<LanguageIdentifier>>(
+    available: &[A],
+) -> Vec<AvailabilityWarning<'_, A>> {
+    let lc = LocaleExpander::new();
+    let maximized: Vec<Option<LanguageIdentifier>> = available
+        .iter()
+        .map(|locale| {
+            let mut maximized = locale.as_ref().clone();
+            (!maximized.language.is_empty()
+                && lc.maximize(&mut maximized) == TransformResult::Modified)
+                .then_some(maximized)
+        })
+        .collect();
 
-                if matches(locale.as_ref(), &$req, $self_as_range, $other_as_range) {
-                    match_found = true;
-                    supported_locales.push(*locale);
-                    return false;
+    let mut warnings = vec![];
+    for (i, first) in available.iter().enumerate() {
+        for (j, second) in available.iter().enumerate().skip(i + 1) {
+            let (langid1, langid2) = (first.as_ref(), second.as_ref());
+            let warning = if langid1 == langid2 {
+                AvailabilityWarning::Duplicate { first, second }
+            } else if crate::langid::is_equivalent(langid1, langid2) {
+                AvailabilityWarning::Alias { first, second }
+            } else if maximized[i].as_ref() == Some(langid2) {
+                AvailabilityWarning::Maximized {
+                    locale: first,
+                    maximized: second,
                 }
-                true
+            } else if maximized[j].as_ref() == Some(langid1) {
+                AvailabilityWarning::Maximized {
+                    locale: second,
+                    maximized: first,
+                }
+            } else {
+                continue;
+            };
+            warnings.push(warning);
+        }
+    }
+    warnings
+}
+
+fn join_list<I: IntoIterator<Item = T>, T: Display>(items: I) -> String {
+    items
+        .into_iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Returns a single line describing the negotiation, for logging.
+///
+/// See [`Negotiator::explain`].
+#[must_use]
+pub fn explain<
+    R: AsRef<LanguageIdentifier> + Display,
+    A: AsRef<LanguageIdentifier> + PartialEq + Display,
+>(
+    requested: &[R],
+    available: &[A],
+    default: Option<&A>,
+    strategy: NegotiationStrategy,
+) -> String {
+    Negotiator::new(strategy).explain(requested, available, default)
+}
+
+/// Controls when the default locale is appended to the negotiated list.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum DefaultBehavior {
+    /// Append the default unless it is already in the list.
+    AppendIfMissing,
+    /// Append the default only if no locale has been negotiated.
+    AppendIfEmpty,
+    /// Always place the default last, moving it there if it has been negotiated.
+    AppendAlways,
+    /// Never append the default.
+    Never,
+}
+
+/// Controls where the default locale is placed in the negotiated list.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum DefaultPosition {
+    /// Keep the default where it has been matched, or where it has been appended.
+    #[default]
+    Natural,
+    /// Move the default to the front of the list.
+    First,
+    /// Move the default to the end of the list.
+    Last,
+}
+
+/// Controls which of the equally preferred available locales matched at the
+/// same step comes first.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum AvailablePreference {
+    /// Prefer the locales placed earlier in the available list, like when it is
+    /// ordered from the best translation.
+    #[default]
+    FirstWins,
+    /// Prefer the locales placed later in the available list, like when
+    /// overrides are appended to it.
+    LastWins,
+}
+
+impl From<NegotiationStrategy> for DefaultBehavior {
+    /// Returns the behavior used by [`negotiate_languages`] for a given strategy.
+    fn from(strategy: NegotiationStrategy) -> Self {
+        match strategy {
+            NegotiationStrategy::Filtering | NegotiationStrategy::Matching => Self::AppendIfMissing,
+            NegotiationStrategy::Lookup => Self::AppendIfEmpty,
+        }
+    }
+}
+
+/// A configurable language negotiator.
+///
+/// [`negotiate_languages`] is a shorthand for a `Negotiator` with the default
+/// configuration for a given strategy.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::convert_vec_str_to_langids_lossy;
+/// use fluent_langneg::negotiate::{DefaultBehavior, Negotiator};
+/// use fluent_langneg::NegotiationStrategy;
+/// use icu_locid::LanguageIdentifier;
+///
+/// let requested = convert_vec_str_to_langids_lossy(&["pl"]);
+/// let available = convert_vec_str_to_langids_lossy(&["it", "de"]);
+/// let default: LanguageIdentifier = "en-US".parse().expect("Parsing langid failed.");
+///
+/// let supported = Negotiator::new(NegotiationStrategy::Filtering)
+///     .with_default_behavior(DefaultBehavior::Never)
+///     .negotiate(&requested, &available, Some(&default));
+///
+/// assert!(supported.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Negotiator {
+    strategy: NegotiationStrategy,
+    default_behavior: DefaultBehavior,
+    default_position: DefaultPosition,
+    min_acceptable_level: Option<MatchKind>,
+    consume_matched: bool,
+    macrolanguage_fallback: MacrolanguageFallback,
+    equivalences: Vec<Vec<Language>>,
+    paradigm_locales: Vec<LanguageIdentifier>,
+    region_priority: Vec<(Language, Vec<Region>)>,
+    sticky_locale: Option<LanguageIdentifier>,
+    bare_language_matches_all_regions: Option<bool>,
+    fallback_script: Option<Script>,
+    assume_language: Option<Language>,
+    max_comparisons: Option<usize>,
+    excluded_locales: Vec<LanguageIdentifier>,
+    script_fallbacks: Vec<(Language, Script, Script)>,
+    include_unmatched: bool,
+    available_weights: HashMap<String, f32>,
+    available_preference: AvailablePreference,
+}
+
+impl Negotiator {
+    #[must_use]
+    pub fn new(strategy: NegotiationStrategy) -> Self {
+        Self {
+            strategy,
+            default_behavior: strategy.into(),
+            default_position: DefaultPosition::Natural,
+            min_acceptable_level: None,
+            consume_matched: true,
+            macrolanguage_fallback: MacrolanguageFallback::Disabled,
+            equivalences: vec![],
+            paradigm_locales: vec![],
+            region_priority: vec![],
+            sticky_locale: None,
+            bare_language_matches_all_regions: None,
+            fallback_script: None,
+            assume_language: None,
+            max_comparisons: None,
+            excluded_locales: vec![],
+            script_fallbacks: vec![],
+            include_unmatched: false,
+            available_weights: HashMap::new(),
+            available_preference: AvailablePreference::FirstWins,
+        }
+    }
+
+    #[must_use]
+    pub fn with_default_behavior(mut self, default_behavior: DefaultBehavior) -> Self {
+        self.default_behavior = default_behavior;
+        self
+    }
+
+    /// Controls where the default locale is placed, if it is in the negotiated list.
+    #[must_use]
+    pub fn with_default_position(mut self, default_position: DefaultPosition) -> Self {
+        self.default_position = default_position;
+        self
+    }
+
+    /// Discards matches worse than `level`.
+    ///
+    /// If no match is good enough, the default locale is used instead. With
+    /// [`MatchKind::AvailableRange`], only available locales equal to the
+    /// requested ones or covering them as ranges are matched, like `de` for
+    /// `de-DE`, but not `de-DE` for `de`.
+    #[must_use]
+    pub fn with_min_acceptable_level(mut self, level: MatchKind) -> Self {
+        self.min_acceptable_level = Some(level);
+        self
+    }
+
+    /// Controls whether an available locale matched by one requested locale
+    /// can still be matched by the following requested locales.
+    ///
+    /// By default, matched available locales are consumed, so each requested
+    /// locale is matched against the remaining ones. When disabled, each
+    /// requested locale is matched against all available locales, and the
+    /// result is an ordered union of the matches without duplicates.
+    #[must_use]
+    pub fn with_consume_matched(mut self, consume_matched: bool) -> Self {
+        self.consume_matched = consume_matched;
+        self
+    }
+
+    /// Enables matching through ISO 639-3 macrolanguages after all other steps.
+    ///
+    /// The fallback is disabled by default, since an individual language, like
+    /// Cantonese, may not be acceptable to users of its macrolanguage.
+    #[must_use]
+    pub fn with_macrolanguage_fallback(mut self, fallback: MacrolanguageFallback) -> Self {
+        self.macrolanguage_fallback = fallback;
+        self
+    }
+
+    /// Declares groups of languages which are interchangeable for the content,
+    /// so that a requested language matches any other language in its group.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{Negotiator, NegotiationStrategy};
+    /// use icu_locid::{langid, subtags::language};
+    ///
+    /// let negotiator = Negotiator::new(NegotiationStrategy::Filtering).with_equivalences(&[
+    ///     &[language!("nb"), language!("nn"), language!("no")],
+    ///     &[language!("id"), language!("ms")],
+    /// ]);
+    ///
+    /// let available = [langid!("de"), langid!("nn")];
+    /// assert_eq!(
+    ///     negotiator.negotiate(&[langid!("nb")], &available, None),
+    ///     &[&langid!("nn")]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_equivalences(mut self, equivalences: &[&[Language]]) -> Self {
+        self.equivalences = equivalences.iter().map(|group| group.to_vec()).collect();
+        self
+    }
+
+    /// Declares paradigm locales, which are preferred over other available
+    /// locales matched at the same step, in the given order.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Negotiator, NegotiationStrategy};
+    /// use icu_locid::langid;
+    ///
+    /// let negotiator = Negotiator::new(NegotiationStrategy::Lookup)
+    ///     .with_paradigm_locales(&[langid!("en-GB"), langid!("en-US")]);
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["en-IN"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["en-001", "en-GB"]);
+    /// assert_eq!(
+    ///     negotiator.negotiate(&requested, &available, None),
+    ///     &[&langid!("en-GB")]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_paradigm_locales(mut self, paradigm_locales: &[LanguageIdentifier]) -> Self {
+        self.paradigm_locales = paradigm_locales.to_vec();
+        self
+    }
+
+    /// Declares the preferred order of regions of a language, used when
+    /// multiple available locales are matched at the same step, like when
+    /// regions are replaced with a range in step 6.
+    ///
+    /// Locales in regions which are not listed follow the listed ones. Paradigm
+    /// locales take precedence over the region order.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Negotiator, NegotiationStrategy};
+    /// use icu_locid::{langid, subtags::{language, region}};
+    ///
+    /// let negotiator = Negotiator::new(NegotiationStrategy::Lookup)
+    ///     .with_region_priority(language!("en"), &[region!("US"), region!("GB")]);
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["en-IE"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["en-AU", "en-GB", "en-US"]);
+    /// assert_eq!(
+    ///     negotiator.negotiate(&requested, &available, None),
+    ///     &[&langid!("en-US")]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_region_priority(mut self, language: Language, regions: &[Region]) -> Self {
+        self.region_priority.retain(|(l, _)| *l != language);
+        self.region_priority.push((language, regions.to_vec()));
+        self
+    }
+
+    /// Controls whether a requested locale without a region, like `en`, is
+    /// treated as a range of all its regions.
+    ///
+    /// When enabled, it matches available locales in any region at step 2,
+    /// like `en-US` and `en-GB`, in their available order. When disabled, it
+    /// only matches available locales without a region, or in its likely
+    /// region at step 3.
+    ///
+    /// By default, neither applies: such a locale matches its likely region at
+    /// step 3, and other regions at step 6.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Negotiator, NegotiationStrategy};
+    /// use icu_locid::langid;
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["en"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["en-GB", "en-US"]);
+    /// let negotiator = Negotiator::new(NegotiationStrategy::Filtering);
+    ///
+    /// assert_eq!(
+    ///     negotiator
+    ///         .clone()
+    ///         .with_bare_language_matches_all_regions(false)
+    ///         .negotiate(&requested, &available, None),
+    ///     &[&langid!("en-US")]
+    /// );
+    /// assert_eq!(
+    ///     negotiator
+    ///         .with_bare_language_matches_all_regions(true)
+    ///         .negotiate(&requested, &available, None),
+    ///     &[&langid!("en-GB"), &langid!("en-US")]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_bare_language_matches_all_regions(mut self, matches_all_regions: bool) -> Self {
+        self.bare_language_matches_all_regions = Some(matches_all_regions);
+        self
+    }
+
+    /// Declares a script assumed for requested locales without one, when
+    /// there is no likely subtags data for their language.
+    ///
+    /// This is a heuristic for long-tail languages, most of which are written
+    /// in the Latin script, so that step 3 can still prefer an available locale
+    /// in that script. It is only used in step 3, so if the guess is wrong for
+    /// a given language, locales in other scripts are still matched afterwards.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Negotiator, NegotiationStrategy};
+    /// use icu_locid::{langid, subtags::script};
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["qaa"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["qaa-Cyrl", "qaa-Latn"]);
+    ///
+    /// let negotiator = Negotiator::new(NegotiationStrategy::Lookup)
+    ///     .with_fallback_script(script!("Latn"));
+    /// assert_eq!(
+    ///     negotiator.negotiate(&requested, &available, None),
+    ///     &[&langid!("qaa-Latn")]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_fallback_script(mut self, script: Script) -> Self {
+        self.fallback_script = Some(script);
+        self
+    }
+
+    /// Declares a language assumed for requested locales without one, like
+    /// `und-XK`, when there is no likely subtags data for them.
+    ///
+    /// By default, such requested locales only match in steps 1 and 2, as
+    /// likely subtags are not added to them. With an assumed language, they
+    /// go through the remaining steps with the likely language of their region
+    /// or script, or with the assumed language if the data has none. This is
+    /// a heuristic for negotiating by region, where the data may be incomplete.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Negotiator, NegotiationStrategy};
+    /// use icu_locid::{langid, subtags::language};
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["und-XK"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["en", "sq", "sr"]);
+    ///
+    /// let negotiator = Negotiator::new(NegotiationStrategy::Lookup)
+    ///     .with_assume_language(language!("sq"));
+    /// assert_eq!(
+    ///     negotiator.negotiate(&requested, &available, None),
+    ///     &[&langid!("sq")]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_assume_language(mut self, language: Language) -> Self {
+        self.assume_language = Some(language);
+        self
+    }
+
+    /// Declares that a language written in the script `from` can fall back to
+    /// available locales written in the script `to`, like Mongolian in the
+    /// traditional script to Mongolian in Cyrillic.
+    ///
+    /// Locales with different scripts otherwise never match. The fallback is
+    /// tried in step 6b, after every other locale of the requested script,
+    /// and applies in one direction only. Declaring another fallback for the
+    /// same language and script replaces the previous one.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Negotiator, NegotiationStrategy};
+    /// use icu_locid::{langid, subtags::{language, script}};
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["mn-Mong"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["en-US", "mn-Cyrl"]);
+    ///
+    /// let negotiator = Negotiator::new(NegotiationStrategy::Lookup)
+    ///     .with_script_fallback(language!("mn"), script!("Mong"), script!("Cyrl"));
+    /// assert_eq!(
+    ///     negotiator.negotiate(&requested, &available, None),
+    ///     &[&langid!("mn-Cyrl")]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_script_fallback(mut self, language: Language, from: Script, to: Script) -> Self {
+        self.script_fallbacks
+            .retain(|(l, s, _)| *l != language || *s != from);
+        self.script_fallbacks.push((language, from, to));
+        self
+    }
+
+    /// Limits the number of comparisons between requested and available
+    /// locales, for negotiating untrusted input, like an `Accept-Language`
+    /// header with a huge number of locales.
+    ///
+    /// Each step counts the number of available locales it tests. Once the
+    /// limit is exceeded, the negotiation stops, and the locales matched so far
+    /// are returned, followed by the default locale.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Negotiator, NegotiationStrategy};
+    /// use icu_locid::langid;
+    ///
+    /// let mut requested = vec!["de"; 1000];
+    /// requested.push("fr");
+    /// let requested = convert_vec_str_to_langids_lossy(requested);
+    /// let available = convert_vec_str_to_langids_lossy(&["en-US", "fr"]);
+    ///
+    /// let negotiator = Negotiator::new(NegotiationStrategy::Filtering).with_max_comparisons(100);
+    /// assert_eq!(
+    ///     negotiator.negotiate(&requested, &available, Some(&available[0])),
+    ///     &[&langid!("en-US")]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_max_comparisons(mut self, max_comparisons: usize) -> Self {
+        self.max_comparisons = Some(max_comparisons);
+        self
+    }
+
+    /// Excludes available locales equal to any of the given ones, as if they
+    /// were absent, so the negotiation falls through to the next candidates.
+    ///
+    /// This allows temporarily disabling locales, like ones behind a feature
+    /// flag, without rebuilding the list of available locales.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Negotiator, NegotiationStrategy};
+    /// use icu_locid::langid;
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["de-CH"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["de-CH", "de-DE"]);
+    ///
+    /// let negotiator = Negotiator::new(NegotiationStrategy::Lookup)
+    ///     .with_excluded_locales(&[langid!("de-CH")]);
+    /// assert_eq!(
+    ///     negotiator.negotiate(&requested, &available, None),
+    ///     &[&langid!("de-DE")]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_excluded_locales(mut self, excluded_locales: &[LanguageIdentifier]) -> Self {
+        self.excluded_locales = excluded_locales.to_vec();
+        self
+    }
+
+    /// Declares scores of available locales, like from usage data, which order
+    /// available locales matched at the same step from the highest score.
+    ///
+    /// The scores are a tie-breaker used after paradigm locales, region
+    /// priorities and, with the `Filtering` strategy, specificity. Keys are
+    /// language tags, and locales without a score have a score of `0.0`.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Negotiator, NegotiationStrategy};
+    /// use icu_locid::langid;
+    /// use std::collections::HashMap;
+    ///
+    /// let negotiator = Negotiator::new(NegotiationStrategy::Filtering)
+    ///     .with_available_weights(HashMap::from([
+    ///         ("en-US".to_string(), 0.9),
+    ///         ("en-GB".to_string(), 0.4),
+    ///     ]));
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["en"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["en-GB", "en-US"]);
+    /// assert_eq!(
+    ///     negotiator.negotiate(&requested, &available, None),
+    ///     &[&langid!("en-US"), &langid!("en-GB")]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_available_weights(mut self, available_weights: HashMap<String, f32>) -> Self {
+        self.available_weights = normalize_weights(available_weights);
+        self
+    }
+
+    /// Controls which available locale comes first among the ones matched at
+    /// the same step and equally preferred otherwise.
+    ///
+    /// By default, it is the one placed first in the available list.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Negotiator, NegotiationStrategy};
+    /// use fluent_langneg::negotiate::AvailablePreference;
+    /// use icu_locid::langid;
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["es-MX"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["es-AR", "es-CO"]);
+    ///
+    /// let negotiator = Negotiator::new(NegotiationStrategy::Lookup)
+    ///     .with_available_preference(AvailablePreference::LastWins);
+    /// assert_eq!(
+    ///     negotiator.negotiate(&requested, &available, None),
+    ///     &[&langid!("es-CO")]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_available_preference(mut self, available_preference: AvailablePreference) -> Self {
+        self.available_preference = available_preference;
+        self
+    }
+
+    /// Controls whether [`Negotiator::negotiate_detailed`] includes the
+    /// requested locales without a match, like for planning which locales
+    /// need to be translated.
+    ///
+    /// They are placed in the order of the requested locales, among the
+    /// locales matched by the other requested locales. See
+    /// [`Negotiator::unserved`] for which requested locales are unmatched.
+    #[must_use]
+    pub fn with_include_unmatched(mut self, include_unmatched: bool) -> Self {
+        self.include_unmatched = include_unmatched;
+        self
+    }
+
+    /// Declares a locale previously chosen by the user, which is placed first
+    /// if it is available and matches any of the requested locales, at any step.
+    ///
+    /// This keeps the negotiated locale stable across small changes of the
+    /// requested locales, like a different `Accept-Language` header.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Negotiator, NegotiationStrategy};
+    /// use icu_locid::langid;
+    ///
+    /// let negotiator = Negotiator::new(NegotiationStrategy::Lookup)
+    ///     .with_sticky_locale(langid!("en-GB"));
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["en-US", "de"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["de", "en-US", "en-GB"]);
+    /// assert_eq!(
+    ///     negotiator.negotiate(&requested, &available, None),
+    ///     &[&langid!("en-GB")]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_sticky_locale(mut self, locale: LanguageIdentifier) -> Self {
+        self.sticky_locale = Some(locale);
+        self
+    }
+
+    /// Returns the available locales matching the requested ones, without the default locale.
+    ///
+    /// The result is ordered by the requested locales they matched, and for each
+    /// requested locale, by the step at which they matched. Locales matched at the
+    /// same step are ordered by [`Negotiator::with_paradigm_locales`] and
+    /// [`Negotiator::with_region_priority`], then from the most to the least
+    /// specific, then by [`Negotiator::with_available_weights`], and otherwise
+    /// keep the order of the available list, unless it is reversed by
+    /// [`Negotiator::with_available_preference`].
+    #[must_use]
+    pub fn filter_matches<
+        'a,
+        R: 'a + AsRef<LanguageIdentifier>,
+        A: 'a + AsRef<LanguageIdentifier>,
+    >(
+        &self,
+        requested: &[R],
+        available: &'a [A],
+    ) -> Vec<&'a A> {
+        self.unique_matches(requested, available, usize::MAX)
+            .into_iter()
+            .map(|m| m.locale)
+            .collect()
+    }
+
+    /// Returns up to `limit` matches of [`Negotiator::filter_matches`], without duplicates.
+    fn unique_matches<'a, R: AsRef<LanguageIdentifier>, A: 'a + AsRef<LanguageIdentifier>>(
+        &self,
+        requested: &[R],
+        available: &'a [A],
+        limit: usize,
+    ) -> Vec<Match<'a, A>> {
+        let by_specificity = self.strategy == NegotiationStrategy::Filtering;
+        self.unique_matches_ordered(requested, available, limit, by_specificity)
+    }
+
+    /// Returns matches like [`Negotiator::unique_matches`], ordering locales
+    /// matched at the same step by specificity if `by_specificity` is set.
+    fn unique_matches_ordered<
+        'a,
+        R: AsRef<LanguageIdentifier>,
+        A: 'a + AsRef<LanguageIdentifier>,
+    >(
+        &self,
+        requested: &[R],
+        available: &'a [A],
+        limit: usize,
+        by_specificity: bool,
+    ) -> Vec<Match<'a, A>> {
+        let mut matches: Vec<Match<A>> = vec![];
+        for m in self.find_matches_ordered(requested, available, limit, by_specificity) {
+            if self.consume_matched || !matches.iter().any(|l| std::ptr::eq(l.locale, m.locale)) {
+                matches.push(m);
+            }
+        }
+        if let Some(sticky) = self.sticky_match(requested, available) {
+            matches.retain(|m| !std::ptr::eq(m.locale, sticky.locale));
+            matches.insert(0, sticky);
+            if self.strategy == NegotiationStrategy::Lookup {
+                matches.truncate(1);
+            }
+        }
+        matches.truncate(limit);
+        matches
+    }
+
+    /// Returns the match of the sticky locale, if it is available and matches
+    /// any of the requested locales.
+    fn sticky_match<'a, R: AsRef<LanguageIdentifier>, A: 'a + AsRef<LanguageIdentifier>>(
+        &self,
+        requested: &[R],
+        available: &'a [A],
+    ) -> Option<Match<'a, A>> {
+        let sticky = self.sticky_locale.as_ref()?;
+        let idx = available.iter().position(|a| a.as_ref() == sticky)?;
+        let negotiator = Self {
+            strategy: NegotiationStrategy::Lookup,
+            consume_matched: false,
+            sticky_locale: None,
+            ..self.clone()
+        };
+        negotiator
+            .find_matches(requested, &available[idx..=idx], 1)
+            .into_iter()
+            .next()
+    }
+
+    /// Returns the step at which the available locale matches the requested one,
+    /// or `None` if it doesn't match.
+    ///
+    /// The strategy of the negotiator is not significant.
+    #[must_use]
+    pub fn match_level<R: AsRef<LanguageIdentifier>, A: AsRef<LanguageIdentifier>>(
+        &self,
+        requested: &R,
+        available: &A,
+    ) -> Option<MatchKind> {
+        self.find_matches(
+            std::slice::from_ref(requested),
+            std::slice::from_ref(available),
+            1,
+        )
+        .first()
+        .map(|m| m.kind)
+    }
+
+    /// Returns up to `n` best available locales matching the requested ones,
+    /// without the default locale.
+    ///
+    /// The result is the beginning of [`Negotiator::filter_matches`], but the
+    /// negotiation stops once enough locales have been matched.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Negotiator, NegotiationStrategy};
+    /// use icu_locid::langid;
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["de", "fr", "en"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["en-US", "fr-CA", "de-AT", "de-DE"]);
+    ///
+    /// assert_eq!(
+    ///     Negotiator::new(NegotiationStrategy::Filtering).negotiate_top_n(&requested, &available, 3),
+    ///     &[&langid!("de-DE"), &langid!("de-AT"), &langid!("fr-CA")]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn negotiate_top_n<
+        'a,
+        R: 'a + AsRef<LanguageIdentifier>,
+        A: 'a + AsRef<LanguageIdentifier>,
+    >(
+        &self,
+        requested: &[R],
+        available: &'a [A],
+        n: usize,
+    ) -> Vec<&'a A> {
+        self.unique_matches(requested, available, n)
+            .into_iter()
+            .map(|m| m.locale)
+            .collect()
+    }
+
+    /// Returns every available locale matching the requested ones, ranked from
+    /// the best to the worst, with the step at which each has been matched,
+    /// without the default locale.
+    ///
+    /// The locales are negotiated with the `Filtering` strategy, whatever the
+    /// strategy of the negotiator, so that the candidates `Lookup` or `Matching`
+    /// would have skipped can be inspected, like for logging the runner-ups.
+    /// The other settings of the negotiator still apply, and locales matched
+    /// at the same step are ordered like its strategy picks them, so the first
+    /// locale is the one the negotiator would return first.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, MatchKind, Negotiator, NegotiationStrategy};
+    /// use icu_locid::langid;
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["pt-BR", "fr"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["fr", "pt-PT", "pt"]);
+    ///
+    /// let negotiator = Negotiator::new(NegotiationStrategy::Lookup);
+    /// assert_eq!(
+    ///     negotiator.negotiate_ranked(&requested, &available),
+    ///     &[
+    ///         (&langid!("pt"), MatchKind::AvailableRange),
+    ///         (&langid!("pt-PT"), MatchKind::RegionRange),
+    ///         (&langid!("fr"), MatchKind::Exact),
+    ///     ]
+    /// );
+    /// assert_eq!(negotiator.negotiate(&requested, &available, None), &[&langid!("pt")]);
+    /// ```
+    #[must_use]
+    pub fn negotiate_ranked<
+        'a,
+        R: 'a + AsRef<LanguageIdentifier>,
+        A: 'a + AsRef<LanguageIdentifier>,
+    >(
+        &self,
+        requested: &[R],
+        available: &'a [A],
+    ) -> Vec<(&'a A, MatchKind)> {
+        let negotiator = Self {
+            strategy: NegotiationStrategy::Filtering,
+            ..self.clone()
+        };
+        // Locales matched at the same step are ordered like the strategy of
+        // the negotiator would pick them.
+        let by_specificity = self.strategy == NegotiationStrategy::Filtering;
+        negotiator
+            .unique_matches_ordered(requested, available, usize::MAX, by_specificity)
+            .into_iter()
+            .map(|m| (m.locale, m.kind))
+            .collect()
+    }
+
+    /// Returns the available locales matching the requested ones, without the
+    /// default locale, grouped by the step at which they have been matched.
+    ///
+    /// Only steps with matches are present, and each group keeps the order of
+    /// the negotiation, so this gives an overview of the quality of the matches.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, MatchKind, Negotiator, NegotiationStrategy};
+    /// use icu_locid::langid;
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["fr", "de-CH", "it"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["fr", "de", "it-IT"]);
+    ///
+    /// let grouped = Negotiator::new(NegotiationStrategy::Filtering).negotiate_grouped(&requested, &available);
+    /// assert_eq!(grouped[&MatchKind::Exact], &[&langid!("fr")]);
+    /// assert_eq!(grouped[&MatchKind::AvailableRange], &[&langid!("de")]);
+    /// assert_eq!(grouped[&MatchKind::LikelySubtags], &[&langid!("it-IT")]);
+    /// assert!(!grouped.contains_key(&MatchKind::RegionRange));
+    /// ```
+    #[must_use]
+    pub fn negotiate_grouped<
+        'a,
+        R: 'a + AsRef<LanguageIdentifier>,
+        A: 'a + AsRef<LanguageIdentifier>,
+    >(
+        &self,
+        requested: &[R],
+        available: &'a [A],
+    ) -> BTreeMap<MatchKind, Vec<&'a A>> {
+        let mut grouped: BTreeMap<MatchKind, Vec<&A>> = BTreeMap::new();
+        for m in self.unique_matches(requested, available, usize::MAX) {
+            grouped.entry(m.kind).or_default().push(m.locale);
+        }
+        grouped
+    }
+
+    /// Returns the first locale [`Negotiator::negotiate`] would return, or
+    /// `None` if it would return none.
+    ///
+    /// The negotiation stops at the first match, so this is cheaper than
+    /// negotiating the full list when a single locale is needed, whatever the
+    /// strategy.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Negotiator, NegotiationStrategy};
+    /// use icu_locid::langid;
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["de-CH", "fr"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["en-US", "fr", "de-DE"]);
+    /// let negotiator = Negotiator::new(NegotiationStrategy::Filtering);
+    ///
+    /// assert_eq!(
+    ///     negotiator.negotiate_one(&requested, &available, Some(&available[0])),
+    ///     Some(&langid!("de-DE"))
+    /// );
+    /// ```
+    #[must_use]
+    pub fn negotiate_one<
+        'a,
+        R: 'a + AsRef<LanguageIdentifier>,
+        A: 'a + AsRef<LanguageIdentifier> + PartialEq,
+    >(
+        &self,
+        requested: &[R],
+        available: &'a [A],
+        default: Option<&'a A>,
+    ) -> Option<&'a A> {
+        let supported = self
+            .unique_matches(requested, available, 1)
+            .into_iter()
+            .map(|m| (Some(()), m.locale))
+            .collect();
+        self.apply_default(supported, default.as_slice(), available)
+            .first()
+            .map(|(_, locale)| *locale)
+    }
+
+    /// Returns the best available locale for each requested locale, in the
+    /// order of requested locales.
+    ///
+    /// Each requested locale is resolved on its own, so an available locale
+    /// can be the best one for several of them, whatever
+    /// [`Negotiator::with_consume_matched`] is set to.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Negotiator, NegotiationStrategy};
+    /// use icu_locid::langid;
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["pl", "fr", "en-US"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["it", "fr", "en-GB"]);
+    ///
+    /// let map = Negotiator::new(NegotiationStrategy::Filtering).negotiate_map(&requested, &available);
+    /// assert_eq!(
+    ///     map,
+    ///     &[
+    ///         (&langid!("pl"), None),
+    ///         (&langid!("fr"), Some(&langid!("fr"))),
+    ///         (&langid!("en-US"), Some(&langid!("en-GB"))),
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn negotiate_map<
+        'a,
+        'r,
+        R: 'r + AsRef<LanguageIdentifier>,
+        A: 'a + AsRef<LanguageIdentifier>,
+    >(
+        &self,
+        requested: &'r [R],
+        available: &'a [A],
+    ) -> Vec<(&'r R, Option<&'a A>)> {
+        let negotiator = Self {
+            consume_matched: false,
+            ..self.clone()
+        };
+        let mut map: Vec<(&R, Option<&A>)> = requested.iter().map(|req| (req, None)).collect();
+        for m in negotiator.find_matches(requested, available, usize::MAX) {
+            map[m.requested].1.get_or_insert(m.locale);
+        }
+        map
+    }
+
+    /// Returns the requested locales which haven't been matched by any available locale.
+    ///
+    /// With the `Lookup` strategy, the negotiation stops at the first match,
+    /// so all requested locales following it are returned as well.
+    #[must_use]
+    pub fn unserved<
+        'a,
+        'r,
+        R: 'r + AsRef<LanguageIdentifier>,
+        A: 'a + AsRef<LanguageIdentifier>,
+    >(
+        &self,
+        requested: &'r [R],
+        available: &'a [A],
+    ) -> Vec<&'r R> {
+        self.negotiate_map(requested, available)
+            .into_iter()
+            .filter_map(|(req, avail)| avail.is_none().then_some(req))
+            .collect()
+    }
+
+    /// Moves available locales matched at a single step of the cascade to the supported ones,
+    /// returning whether any have been matched.
+    fn test_step<'a, A: AsRef<LanguageIdentifier>>(
+        &self,
+        available_locales: &mut Vec<&'a A>,
+        supported_locales: &mut Vec<Match<'a, A>>,
+        requested: usize,
+        kind: MatchKind,
+        by_specificity: bool,
+        matches: impl Fn(&LanguageIdentifier) -> bool,
+    ) -> bool {
+        if self.min_acceptable_level.is_some_and(|level| kind > level) {
+            return false;
+        }
+
+        let last_wins = self.available_preference == AvailablePreference::LastWins;
+
+        if self.strategy != NegotiationStrategy::Filtering {
+            // `min_by` returns the first of equally preferred locales.
+            let candidates = available_locales
+                .iter()
+                .enumerate()
+                .filter(|(_, locale)| matches(locale.as_ref()));
+            let compare = |(_, a): &(usize, &&A), (_, b): &(usize, &&A)| {
+                self.compare_preference(a.as_ref(), b.as_ref(), false)
+            };
+            let best = if last_wins {
+                candidates.rev().min_by(compare)
+            } else {
+                candidates.min_by(compare)
+            }
+            .map(|(idx, _)| idx);
+            if let Some(idx) = best {
+                supported_locales.push(Match {
+                    locale: available_locales.remove(idx),
+                    requested,
+                    kind,
+                });
+            }
+            return best.is_some();
+        }
+
+        let start = supported_locales.len();
+        available_locales.retain(|locale| {
+            if matches(locale.as_ref()) {
+                supported_locales.push(Match {
+                    locale: *locale,
+                    requested,
+                    kind,
+                });
+                return false;
+            }
+            true
+        });
+        let found = &mut supported_locales[start..];
+        if found.len() > 1 {
+            // The sort is stable, so equally preferred locales keep this order.
+            if last_wins {
+                found.reverse();
+            }
+            found.sort_by(|a, b| {
+                self.compare_preference(a.locale.as_ref(), b.locale.as_ref(), by_specificity)
             });
+        }
+        !found.is_empty()
+    }
 
-            if match_found {
-                match strategy {
-                    NegotiationStrategy::Filtering => {}
-                    NegotiationStrategy::Matching => continue,
-                    NegotiationStrategy::Lookup => break,
-                }
+    /// Orders available locales matched at the same step, keeping their
+    /// available order for equally preferred ones.
+    ///
+    /// Paradigm locales come first, then regions by their priority, then, if
+    /// `by_specificity` is set, the most specific locales, like `en-US` before
+    /// `en`, then the highest weights.
+    fn compare_preference(
+        &self,
+        a: &LanguageIdentifier,
+        b: &LanguageIdentifier,
+        by_specificity: bool,
+    ) -> Ordering {
+        let paradigm_rank = |locale: &LanguageIdentifier| {
+            self.paradigm_locales
+                .iter()
+                .position(|p| p == locale)
+                .unwrap_or(self.paradigm_locales.len())
+        };
+        let region_rank = |locale: &LanguageIdentifier| {
+            self.region_priority
+                .iter()
+                .find(|(language, _)| *language == locale.language)
+                .and_then(|(_, regions)| {
+                    let region = locale.region?;
+                    regions.iter().position(|r| *r == region)
+                })
+                .unwrap_or(usize::MAX)
+        };
+        let weight = |locale: &LanguageIdentifier| {
+            if self.available_weights.is_empty() {
+                return 0.0;
             }
-        }};
+            self.available_weights
+                .get(&locale.to_string())
+                .copied()
+                .unwrap_or(0.0)
+        };
+        paradigm_rank(a)
+            .cmp(&paradigm_rank(b))
+            .then_with(|| region_rank(a).cmp(&region_rank(b)))
+            .then_with(|| {
+                if by_specificity {
+                    specificity(b).cmp(&specificity(a))
+                } else {
+                    Ordering::Equal
+                }
+            })
+            .then_with(|| weight(b).total_cmp(&weight(a)))
+    }
+
+    /// Runs the cascade, stopping once at least `limit` locales have been matched.
+    fn find_matches<'a, R: AsRef<LanguageIdentifier>, A: 'a + AsRef<LanguageIdentifier>>(
+        &self,
+        requested: &[R],
+        available: &'a [A],
+        limit: usize,
+    ) -> Vec<Match<'a, A>> {
+        let by_specificity = self.strategy == NegotiationStrategy::Filtering;
+        self.find_matches_ordered(requested, available, limit, by_specificity)
     }
 
-    for req in requested {
-        let req = req.as_ref();
+    /// Runs the cascade like [`Negotiator::find_matches`], ordering locales
+    /// matched at the same step by specificity if `by_specificity` is set.
+    fn find_matches_ordered<'a, R: AsRef<LanguageIdentifier>, A: 'a + AsRef<LanguageIdentifier>>(
+        &self,
+        requested: &[R],
+        available: &'a [A],
+        limit: usize,
+        by_specificity: bool,
+    ) -> Vec<Match<'a, A>> {
+        let strategy = self.strategy;
 
-        // 1) Try to find a simple (case-insensitive) string match for the request.
-        test_strategy!(req, false, false);
+        let mut lc: Option<LocaleExpander> = None;
 
-        // 2) Try to match against the available locales treated as ranges.
-        test_strategy!(req, true, false);
+        let mut supported_locales: Vec<Match<A>> = vec![];
+        let mut requested_idx;
+        let mut region_less_only;
+        let mut comparisons: usize = 0;
 
-        // Per Unicode TR35, 4.4 Locale Matching, we don't add likely subtags to
-        // requested locales, so we'll skip it from the rest of the steps.
-        if req.language.is_empty() {
-            continue;
+        let is_included = |locale: &&A| !self.excluded_locales.contains(locale.as_ref());
+        let mut available_locales: Vec<&A> = available.iter().filter(is_included).collect();
+
+        macro_rules! test_strategy {
+            ($req:ident, $kind:expr, $mode:expr) => {
+                test_strategy!($kind, |locale: &LanguageIdentifier| {
+                    matches_mode(locale, &$req, $mode)
+                })
+            };
+            ($kind:expr, $matches:expr) => {{
+                comparisons += available_locales.len();
+                if self.max_comparisons.is_some_and(|max| comparisons > max) {
+                    break;
+                }
+
+                let step_matches = $matches;
+                let match_found = self.test_step(
+                    &mut available_locales,
+                    &mut supported_locales,
+                    requested_idx,
+                    $kind,
+                    by_specificity,
+                    |locale: &LanguageIdentifier| {
+                        !(region_less_only
+                            && $kind != MatchKind::LikelySubtags
+                            && locale.region.is_some())
+                            && ($kind == MatchKind::Exact || !is_root(locale))
+                            && step_matches(locale)
+                    },
+                );
+
+                if match_found {
+                    match strategy {
+                        NegotiationStrategy::Filtering => {
+                            if self.consume_matched && supported_locales.len() >= limit {
+                                break;
+                            }
+                        }
+                        NegotiationStrategy::Matching => continue,
+                        NegotiationStrategy::Lookup => break,
+                    }
+                }
+            }};
         }
 
-        let mut req = req.to_owned();
-        // 3) Try to match against a maximized version of the requested locale
-        let lc = lc.get_or_insert_with(LocaleExpander::new);
-        if lc.maximize(&mut req) == TransformResult::Modified {
-            test_strategy!(req, true, false);
+        for (idx, req) in requested.iter().enumerate() {
+            if self.consume_matched && supported_locales.len() >= limit {
+                break;
+            }
+            let req = req.as_ref();
+            requested_idx = idx;
+            region_less_only =
+                self.bare_language_matches_all_regions == Some(false) && req.region.is_none();
+
+            if !self.consume_matched {
+                available_locales.clear();
+                available_locales.extend(available.iter().filter(is_included));
+            }
+
+            // Without fallbacks to other languages, only locales of the same
+            // language, or without one, can match, so skip the steps early.
+            if self.equivalences.is_empty()
+                && self.macrolanguage_fallback == MacrolanguageFallback::Disabled
+                && !(req.language.is_empty() && self.assume_language.is_some())
+            {
+                comparisons += available_locales.len();
+                if self.max_comparisons.is_some_and(|max| comparisons > max) {
+                    break;
+                }
+                if !available_locales.iter().any(|locale| {
+                    let language = locale.as_ref().language;
+                    language == req.language || language.is_empty()
+                }) {
+                    continue;
+                }
+            }
+
+            // 1) Try to find a simple (case-insensitive) string match for the request.
+            test_strategy!(req, MatchKind::Exact, RangeMode::Exact);
+
+            // 2) Try to match against the available locales treated as ranges.
+            if self.bare_language_matches_all_regions == Some(true) && req.region.is_none() {
+                test_strategy!(MatchKind::AvailableRange, |locale: &LanguageIdentifier| {
+                    matches_mode(locale, req, RangeMode::SelfIsRange)
+                        || (locale.language == req.language
+                            && subtag_matches(&locale.script, &req.script, RangeMode::SelfIsRange)
+                            && variants_match(
+                                &locale.variants,
+                                &req.variants,
+                                RangeMode::SelfIsRange,
+                            ))
+                });
+            } else {
+                test_strategy!(req, MatchKind::AvailableRange, RangeMode::SelfIsRange);
+            }
+
+            // Per Unicode TR35, 4.4 Locale Matching, we don't add likely subtags to
+            // requested locales, so we'll skip it from the rest of the steps,
+            // unless a language is assumed for them.
+            if req.language.is_empty() && self.assume_language.is_none() {
+                continue;
+            }
+
+            let mut req = req.to_owned();
+            let lc = lc.get_or_insert_with(LocaleExpander::new);
+
+            if let (true, Some(language)) = (req.language.is_empty(), self.assume_language) {
+                let mut maximized = req.clone();
+                lc.maximize(&mut maximized);
+                req.language = if maximized.language.is_empty() {
+                    language
+                } else {
+                    maximized.language
+                };
+            }
+
+            // 2b) Replace deprecated regions with their successors and retry
+            let req_replaced = region_aliases::canonicalize_region(&mut req, lc);
+            let region_alias_matches = |mode| {
+                let (req, lc) = (&req, &*lc);
+                move |locale: &LanguageIdentifier| {
+                    if locale.region.is_some_and(region_aliases::is_alias) {
+                        let mut locale = locale.clone();
+                        region_aliases::canonicalize_region(&mut locale, lc);
+                        matches_mode(&locale, req, mode)
+                    } else {
+                        req_replaced && matches_mode(locale, req, mode)
+                    }
+                }
+            };
+            test_strategy!(
+                MatchKind::RegionAlias,
+                region_alias_matches(RangeMode::Exact)
+            );
+            test_strategy!(
+                MatchKind::RegionAlias,
+                region_alias_matches(RangeMode::SelfIsRange)
+            );
+
+            // 3) Try to match against a maximized version of the requested locale
+            if lc.maximize(&mut req) == TransformResult::Modified {
+                test_strategy!(req, MatchKind::LikelySubtags, RangeMode::SelfIsRange);
+            } else if let (None, Some(script)) = (req.script, self.fallback_script) {
+                req.script = Some(script);
+                test_strategy!(req, MatchKind::LikelySubtags, RangeMode::SelfIsRange);
+                req.script = None;
+            }
+
+            // 4) Try to match against a variant as a range, preferring available
+            //    locales with all of the requested variants
+            if !req.variants.is_empty() {
+                test_strategy!(req, MatchKind::VariantRange, RangeMode::BothRanges);
+            }
+            req.variants.clear();
+            test_strategy!(req, MatchKind::VariantRange, RangeMode::BothRanges);
+
+            // 5) Try to match against the likely subtag without region
+            req.region = None;
+            if lc.maximize(&mut req) == TransformResult::Modified {
+                test_strategy!(
+                    req,
+                    MatchKind::LikelySubtagsWithoutRegion,
+                    RangeMode::SelfIsRange
+                );
+            }
+
+            // 6) Try to match against a region as a range
+            req.region = None;
+            test_strategy!(req, MatchKind::RegionRange, RangeMode::BothRanges);
+
+            // 6b) Try to match against a script declared as a fallback
+            if let Some(&(_, _, script)) = self
+                .script_fallbacks
+                .iter()
+                .find(|(l, s, _)| *l == req.language && Some(*s) == req.script)
+            {
+                let mut req = req.clone();
+                req.script = Some(script);
+                test_strategy!(req, MatchKind::ScriptFallback, RangeMode::BothRanges);
+            }
+
+            // 7) Try to match against languages declared as equivalent
+            let language = req.language;
+            if self.equivalences.iter().any(|g| g.contains(&language)) {
+                test_strategy!(MatchKind::Equivalent, |locale: &LanguageIdentifier| {
+                    locale.language != language
+                        && self
+                            .equivalences
+                            .iter()
+                            .any(|g| g.contains(&language) && g.contains(&locale.language))
+                        && {
+                            let mut req = req.clone();
+                            req.language = locale.language;
+                            matches_mode(locale, &req, RangeMode::BothRanges)
+                        }
+                });
+            }
+
+            // 8) Try to match against the macrolanguage or encompassed languages
+            if self.macrolanguage_fallback != MacrolanguageFallback::Disabled {
+                if let Some(macrolanguage) = macrolanguages::macrolanguage(language) {
+                    req.language = macrolanguage;
+                    test_strategy!(req, MatchKind::Macrolanguage, RangeMode::BothRanges);
+                }
+                if self.macrolanguage_fallback == MacrolanguageFallback::Bidirectional {
+                    test_strategy!(MatchKind::Macrolanguage, |locale: &LanguageIdentifier| {
+                        macrolanguages::macrolanguage(locale.language) == Some(language) && {
+                            let mut req = req.clone();
+                            req.language = locale.language;
+                            matches_mode(locale, &req, RangeMode::BothRanges)
+                        }
+                    });
+                }
+            }
         }
 
-        // 4) Try to match against a variant as a range
-        req.variants.clear();
-        test_strategy!(req, true, true);
+        supported_locales
+    }
+
+    /// Negotiates the requested locales against the available ones.
+    ///
+    /// The result is ordered like in [`Negotiator::filter_matches`], with the
+    /// default locale handled according to the [`DefaultBehavior`].
+    #[must_use]
+    pub fn negotiate<
+        'a,
+        R: 'a + AsRef<LanguageIdentifier>,
+        A: 'a + AsRef<LanguageIdentifier> + PartialEq,
+    >(
+        &self,
+        requested: &[R],
+        available: &'a [A],
+        default: Option<&'a A>,
+    ) -> Vec<&'a A> {
+        self.negotiate_result(requested, available, default).tags()
+    }
+
+    /// Negotiates like [`Negotiator::negotiate`], returning for each locale
+    /// the requested locale and the step which matched it.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, MatchKind, Negotiator, NegotiationStrategy};
+    /// use icu_locid::langid;
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["pl", "fr-CA"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["en-US", "fr"]);
+    ///
+    /// let result = Negotiator::new(NegotiationStrategy::Filtering).negotiate_result(
+    ///     &requested,
+    ///     &available,
+    ///     Some(&available[0]),
+    /// );
+    /// assert_eq!(result.best(), Some(&langid!("fr")));
+    /// assert_eq!(result.matches[0].requested, Some(1));
+    /// assert_eq!(result.matches[0].kind, Some(MatchKind::AvailableRange));
+    /// assert!(result.used_default());
+    /// ```
+    #[must_use]
+    pub fn negotiate_result<
+        'a,
+        R: 'a + AsRef<LanguageIdentifier>,
+        A: 'a + AsRef<LanguageIdentifier> + PartialEq,
+    >(
+        &self,
+        requested: &[R],
+        available: &'a [A],
+        default: Option<&'a A>,
+    ) -> NegotiationResult<'a, A> {
+        let supported = self
+            .unique_matches(requested, available, usize::MAX)
+            .into_iter()
+            .map(|m| (Some((m.requested, m.kind)), m.locale))
+            .collect();
+        let matches = self
+            .apply_default(supported, default.as_slice(), available)
+            .into_iter()
+            .map(|(m, locale)| MatchedLocale {
+                locale,
+                requested: m.map(|(requested, _)| requested),
+                kind: m.map(|(_, kind)| kind),
+            })
+            .collect();
+        NegotiationResult { matches }
+    }
+
+    /// Negotiates like [`Negotiator::negotiate`], with an ordered list of
+    /// default locales.
+    ///
+    /// Depending on the [`DefaultBehavior`], either all default locales which
+    /// are missing from the negotiated list are appended, or only the first one.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Negotiator, NegotiationStrategy};
+    /// use icu_locid::langid;
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["de"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["en", "de-AT", "en-US"]);
+    ///
+    /// assert_eq!(
+    ///     Negotiator::new(NegotiationStrategy::Filtering).negotiate_with_defaults(
+    ///         &requested,
+    ///         &available,
+    ///         &[&available[2], &available[0]],
+    ///     ),
+    ///     &[&langid!("de-AT"), &langid!("en-US"), &langid!("en")]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn negotiate_with_defaults<
+        'a,
+        R: 'a + AsRef<LanguageIdentifier>,
+        A: 'a + AsRef<LanguageIdentifier> + PartialEq,
+    >(
+        &self,
+        requested: &[R],
+        available: &'a [A],
+        defaults: &[&'a A],
+    ) -> Vec<&'a A> {
+        let supported = self
+            .unique_matches(requested, available, usize::MAX)
+            .into_iter()
+            .map(|m| (Some(()), m.locale))
+            .collect();
+        self.apply_default(supported, defaults, available)
+            .into_iter()
+            .map(|(_, locale)| locale)
+            .collect()
+    }
 
-        // 5) Try to match against the likely subtag without region
-        req.region = None;
-        if lc.maximize(&mut req) == TransformResult::Modified {
-            test_strategy!(req, true, false);
+    /// Negotiates like [`Negotiator::negotiate`], and summarizes how well the
+    /// requested locales have been matched.
+    ///
+    /// The [`Confidence`] is based on the best match of each requested locale
+    /// which has been matched, and is as low as the worst of them.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Confidence, Negotiator, NegotiationStrategy};
+    /// use icu_locid::langid;
+    ///
+    /// let negotiator = Negotiator::new(NegotiationStrategy::Lookup);
+    /// let available = convert_vec_str_to_langids_lossy(&["en-US", "de-AT"]);
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["de-DE"]);
+    /// assert_eq!(
+    ///     negotiator.negotiate_with_confidence(&requested, &available, Some(&available[0])),
+    ///     (vec![&langid!("de-AT")], Confidence::Fuzzy)
+    /// );
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["fr"]);
+    /// assert_eq!(
+    ///     negotiator.negotiate_with_confidence(&requested, &available, Some(&available[0])),
+    ///     (vec![&langid!("en-US")], Confidence::DefaultOnly)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn negotiate_with_confidence<
+        'a,
+        R: 'a + AsRef<LanguageIdentifier>,
+        A: 'a + AsRef<LanguageIdentifier> + PartialEq,
+    >(
+        &self,
+        requested: &[R],
+        available: &'a [A],
+        default: Option<&'a A>,
+    ) -> (Vec<&'a A>, Confidence) {
+        let matches = self.unique_matches(requested, available, usize::MAX);
+        let confidence = Confidence::from_matches(&matches);
+        let supported = matches.iter().map(|m| (Some(()), m.locale)).collect();
+        let supported = self
+            .apply_default(supported, default.as_slice(), available)
+            .into_iter()
+            .map(|(_, locale)| locale)
+            .collect();
+        (supported, confidence)
+    }
+
+    /// Negotiates like [`Negotiator::negotiate`], pairing each negotiated locale
+    /// with the requested locale which produced it.
+    ///
+    /// The default locale is paired with `None`, unless it has been matched
+    /// by one of the requested locales.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Negotiator, NegotiationStrategy};
+    /// use icu_locid::langid;
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["fr-CA", "de"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["en-US", "fr"]);
+    ///
+    /// let pairs = Negotiator::new(NegotiationStrategy::Filtering).negotiate_pairs(
+    ///     &requested,
+    ///     &available,
+    ///     Some(&available[0]),
+    /// );
+    /// assert_eq!(
+    ///     pairs,
+    ///     &[
+    ///         (Some(&langid!("fr-CA")), &langid!("fr")),
+    ///         (None, &langid!("en-US")),
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn negotiate_pairs<
+        'a,
+        'r,
+        R: 'r + AsRef<LanguageIdentifier>,
+        A: 'a + AsRef<LanguageIdentifier> + PartialEq,
+    >(
+        &self,
+        requested: &'r [R],
+        available: &'a [A],
+        default: Option<&'a A>,
+    ) -> Vec<(Option<&'r R>, &'a A)> {
+        let supported = self
+            .unique_matches(requested, available, usize::MAX)
+            .into_iter()
+            .map(|m| (Some(&requested[m.requested]), m.locale))
+            .collect();
+        self.apply_default(supported, default.as_slice(), available)
+    }
+
+    /// Negotiates like [`Negotiator::negotiate`], returning each locale with
+    /// the requested locale which produced it.
+    ///
+    /// With [`Negotiator::with_include_unmatched`], the requested locales
+    /// without a match are included as well.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Negotiated, Negotiator, NegotiationStrategy};
+    /// use icu_locid::langid;
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["pl", "fr-CA", "de"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["en-US", "fr"]);
+    ///
+    /// let negotiator = Negotiator::new(NegotiationStrategy::Filtering).with_include_unmatched(true);
+    /// assert_eq!(
+    ///     negotiator.negotiate_detailed(&requested, &available, Some(&available[0])),
+    ///     &[
+    ///         Negotiated::Unmatched(&langid!("pl")),
+    ///         Negotiated::Matched(&langid!("fr-CA"), &langid!("fr")),
+    ///         Negotiated::Unmatched(&langid!("de")),
+    ///         Negotiated::Default(&langid!("en-US")),
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn negotiate_detailed<
+        'a,
+        'r,
+        R: 'r + AsRef<LanguageIdentifier>,
+        A: 'a + AsRef<LanguageIdentifier> + PartialEq,
+    >(
+        &self,
+        requested: &'r [R],
+        available: &'a [A],
+        default: Option<&'a A>,
+    ) -> Vec<Negotiated<'r, 'a, R, A>> {
+        let supported = self
+            .unique_matches(requested, available, usize::MAX)
+            .into_iter()
+            .map(|m| (Some(m.requested), m.locale))
+            .collect();
+        let supported = self.apply_default(supported, default.as_slice(), available);
+
+        let mut unmatched: Vec<usize> = vec![];
+        if self.include_unmatched {
+            unmatched = (0..requested.len())
+                .filter(|idx| !supported.iter().any(|(req, _)| *req == Some(*idx)))
+                .collect();
         }
+        // Trailing default locales stay after the unmatched requested locales.
+        let trailing = supported
+            .iter()
+            .rposition(|(req, _)| req.is_some())
+            .map_or(0, |idx| idx + 1);
 
-        // 6) Try to match against a region as a range
-        req.region = None;
-        test_strategy!(req, true, true);
+        let mut detailed = Vec::with_capacity(supported.len() + unmatched.len());
+        let mut unmatched = unmatched.into_iter().peekable();
+        for (idx, (req, locale)) in supported.into_iter().enumerate() {
+            let until = match req {
+                Some(req) => req,
+                None if idx >= trailing => requested.len(),
+                None => 0,
+            };
+            while let Some(u) = unmatched.next_if(|u| *u < until) {
+                detailed.push(Negotiated::Unmatched(&requested[u]));
+            }
+            detailed.push(match req {
+                Some(req) => Negotiated::Matched(&requested[req], locale),
+                None => Negotiated::Default(locale),
+            });
+        }
+        detailed.extend(unmatched.map(|u| Negotiated::Unmatched(&requested[u])));
+        detailed
     }
 
-    supported_locales
+    /// Returns a single line describing the negotiation, for logging.
+    ///
+    /// Each negotiated locale is followed by the step at which it has been
+    /// matched, or by `default` if it has been appended as the default locale.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use fluent_langneg::{convert_vec_str_to_langids_lossy, Negotiator, NegotiationStrategy};
+    ///
+    /// let requested = convert_vec_str_to_langids_lossy(&["pl", "fr", "en-US"]);
+    /// let available = convert_vec_str_to_langids_lossy(&["it", "fr", "en-GB"]);
+    ///
+    /// assert_eq!(
+    ///     Negotiator::new(NegotiationStrategy::Filtering).explain(
+    ///         &requested,
+    ///         &available,
+    ///         Some(&available[0]),
+    ///     ),
+    ///     "requested [pl, fr, en-US] × available [it, fr, en-GB] → \
+    ///      [fr (exact), en-GB (region-range), it (default)]"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn explain<
+        R: AsRef<LanguageIdentifier> + Display,
+        A: AsRef<LanguageIdentifier> + PartialEq + Display,
+    >(
+        &self,
+        requested: &[R],
+        available: &[A],
+        default: Option<&A>,
+    ) -> String {
+        let supported = self
+            .unique_matches(requested, available, usize::MAX)
+            .into_iter()
+            .map(|m| (Some(m.kind), m.locale))
+            .collect();
+        let supported = self.apply_default(supported, default.as_slice(), available);
+
+        format!(
+            "requested [{}] × available [{}] → [{}]",
+            join_list(requested),
+            join_list(available),
+            join_list(supported.iter().map(|(kind, locale)| match kind {
+                Some(kind) => format!("{} ({})", locale, kind),
+                None => format!("{} (default)", locale),
+            }))
+        )
+    }
+
+    /// Adds the default locales to the negotiated locales, paired with `None`,
+    /// according to the [`DefaultBehavior`] and the [`DefaultPosition`].
+    ///
+    /// Without default locales, an available root locale is used as one.
+    fn apply_default<'a, T, A: AsRef<LanguageIdentifier> + PartialEq>(
+        &self,
+        mut supported: Vec<(Option<T>, &'a A)>,
+        defaults: &[&'a A],
+        available: &'a [A],
+    ) -> Vec<(Option<T>, &'a A)> {
+        let root = available.iter().find(|locale| {
+            is_root(locale.as_ref()) && !self.excluded_locales.contains(locale.as_ref())
+        });
+        let defaults = match (defaults.is_empty(), root) {
+            (true, Some(root)) => &[root],
+            _ => defaults,
+        };
+        let is_default = |locale: &A| defaults.contains(&locale);
+
+        match self.default_behavior {
+            DefaultBehavior::AppendIfMissing => {
+                for default in defaults {
+                    if !supported.iter().any(|(_, locale)| locale == default) {
+                        supported.push((None, default));
+                    }
+                }
+            }
+            DefaultBehavior::AppendIfEmpty => {
+                if let (true, Some(default)) = (supported.is_empty(), defaults.first()) {
+                    supported.push((None, default));
+                }
+            }
+            DefaultBehavior::AppendAlways => {
+                supported.retain(|(_, locale)| !is_default(locale));
+                for default in defaults {
+                    if !supported.iter().any(|(_, locale)| locale == default) {
+                        supported.push((None, default));
+                    }
+                }
+            }
+            DefaultBehavior::Never => {}
+        }
+
+        if self.default_position != DefaultPosition::Natural {
+            let (mut defaults, mut others): (Vec<_>, Vec<_>) = supported
+                .into_iter()
+                .partition(|(_, locale)| is_default(locale));
+            supported = match self.default_position {
+                DefaultPosition::First => {
+                    defaults.append(&mut others);
+                    defaults
+                }
+                _ => {
+                    others.append(&mut defaults);
+                    others
+                }
+            };
+        }
+        supported
+    }
 }
 
+/// Negotiates the requested locales against the available ones.
+///
+/// The result is ordered like in [`filter_matches`], followed by the default
+/// locale if it has been appended, see [`DefaultBehavior`].
+///
+/// This is a shorthand for [`Negotiator::negotiate`] with the default
+/// configuration for the strategy.
+#[must_use]
 pub fn negotiate_languages<
     'a,
     R: 'a + AsRef<LanguageIdentifier>,
@@ -248,16 +2766,215 @@ pub fn negotiate_languages<
     default: Option<&'a A>,
     strategy: NegotiationStrategy,
 ) -> Vec<&'a A> {
-    let mut supported = filter_matches(requested, available, strategy);
+    Negotiator::new(strategy).negotiate(requested, available, default)
+}
+
+/// Negotiates like [`negotiate_languages`], with an ordered list of default locales.
+///
+/// See [`Negotiator::negotiate_with_defaults`].
+#[must_use]
+pub fn negotiate_languages_with_defaults<
+    'a,
+    R: 'a + AsRef<LanguageIdentifier>,
+    A: 'a + AsRef<LanguageIdentifier> + PartialEq,
+>(
+    requested: &[R],
+    available: &'a [A],
+    defaults: &[&'a A],
+    strategy: NegotiationStrategy,
+) -> Vec<&'a A> {
+    Negotiator::new(strategy).negotiate_with_defaults(requested, available, defaults)
+}
+
+/// Negotiates like [`negotiate_languages`], and summarizes how well the
+/// requested locales have been matched.
+///
+/// See [`Negotiator::negotiate_with_confidence`].
+#[must_use]
+pub fn negotiate_with_confidence<
+    'a,
+    R: 'a + AsRef<LanguageIdentifier>,
+    A: 'a + AsRef<LanguageIdentifier> + PartialEq,
+>(
+    requested: &[R],
+    available: &'a [A],
+    default: Option<&'a A>,
+    strategy: NegotiationStrategy,
+) -> (Vec<&'a A>, Confidence) {
+    Negotiator::new(strategy).negotiate_with_confidence(requested, available, default)
+}
+
+/// Negotiates like [`negotiate_languages`], pairing each negotiated locale with
+/// the requested locale which produced it, or `None` for the default locale.
+///
+/// See [`Negotiator::negotiate_pairs`] for details.
+#[must_use]
+pub fn negotiate_pairs<
+    'a,
+    'r,
+    R: 'r + AsRef<LanguageIdentifier>,
+    A: 'a + AsRef<LanguageIdentifier> + PartialEq,
+>(
+    requested: &'r [R],
+    available: &'a [A],
+    default: Option<&'a A>,
+    strategy: NegotiationStrategy,
+) -> Vec<(Option<&'r R>, &'a A)> {
+    Negotiator::new(strategy).negotiate_pairs(requested, available, default)
+}
 
-    if let Some(default) = default {
-        if strategy == NegotiationStrategy::Lookup {
-            if supported.is_empty() {
-                supported.push(default);
+/// Negotiates the requested locales against the available ones, returning
+/// how each locale has been found.
+///
+/// See [`Negotiator::negotiate_result`] for details.
+#[must_use]
+pub fn negotiate_result<
+    'a,
+    R: 'a + AsRef<LanguageIdentifier>,
+    A: 'a + AsRef<LanguageIdentifier> + PartialEq,
+>(
+    requested: &[R],
+    available: &'a [A],
+    default: Option<&'a A>,
+    strategy: NegotiationStrategy,
+) -> NegotiationResult<'a, A> {
+    Negotiator::new(strategy).negotiate_result(requested, available, default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use icu_locid::langid;
+
+    #[test]
+    fn test_matches_mode() {
+        let en = langid!("en");
+        let en_us = langid!("en-US");
+        let en_gb = langid!("en-GB");
+        let modes = [
+            RangeMode::Exact,
+            RangeMode::SelfIsRange,
+            RangeMode::OtherIsRange,
+            RangeMode::BothRanges,
+        ];
+
+        for mode in modes {
+            assert!(matches_mode(&en_us, &en_us, mode));
+            assert!(!matches_mode(&en_us, &en_gb, mode));
+        }
+
+        let results: Vec<_> = modes
+            .iter()
+            .map(|&mode| {
+                (
+                    matches_mode(&en, &en_us, mode),
+                    matches_mode(&en_us, &en, mode),
+                )
+            })
+            .collect();
+        assert_eq!(
+            results,
+            &[(false, false), (true, false), (false, true), (true, true)]
+        );
+
+        let und = LanguageIdentifier::UND;
+        assert!(!matches_mode(&und, &en, RangeMode::Exact));
+        assert!(matches_mode(&und, &en, RangeMode::SelfIsRange));
+        assert!(!matches_mode(&und, &en, RangeMode::OtherIsRange));
+
+        let de_1901 = langid!("de-1901");
+        let de_1901_1996: LanguageIdentifier = "de-1901-1996".parse().unwrap();
+        assert!(!matches_mode(&de_1901, &de_1901_1996, RangeMode::Exact));
+        assert!(matches_mode(
+            &de_1901,
+            &de_1901_1996,
+            RangeMode::SelfIsRange
+        ));
+        assert!(!matches_mode(
+            &de_1901,
+            &de_1901_1996,
+            RangeMode::OtherIsRange
+        ));
+        assert!(matches_mode(
+            &de_1901_1996,
+            &de_1901,
+            RangeMode::OtherIsRange
+        ));
+    }
+
+    #[test]
+    fn test_matches_mode_language_only() {
+        // The comparison of all subtags, without the shortcut for bare languages.
+        fn matches_all_subtags(
+            lid1: &LanguageIdentifier,
+            lid2: &LanguageIdentifier,
+            mode: RangeMode,
+        ) -> bool {
+            (lid1.language == lid2.language
+                || (mode.self_is_range() && lid1.language.is_empty())
+                || (mode.other_is_range() && lid2.language.is_empty()))
+                && subtag_matches(&lid1.script, &lid2.script, mode)
+                && subtag_matches(&lid1.region, &lid2.region, mode)
+                && variants_match(&lid1.variants, &lid2.variants, mode)
+        }
+
+        let langids: Vec<LanguageIdentifier> = [
+            "en",
+            "en-US",
+            "en-Latn",
+            "en-Latn-US",
+            "en-US-posix",
+            "en-posix",
+            "en-1901",
+            "de",
+            "de-DE-1901-1996",
+            "und",
+            "und-US",
+            "und-posix",
+        ]
+        .iter()
+        .map(|tag| tag.parse().unwrap())
+        .collect();
+        let modes = [
+            RangeMode::Exact,
+            RangeMode::SelfIsRange,
+            RangeMode::OtherIsRange,
+            RangeMode::BothRanges,
+        ];
+
+        for lid1 in &langids {
+            for lid2 in &langids {
+                for mode in modes {
+                    assert_eq!(
+                        matches_mode(lid1, lid2, mode),
+                        matches_all_subtags(lid1, lid2, mode),
+                        "{lid1}, {lid2}, {mode:?}"
+                    );
+                }
             }
-        } else if !supported.contains(&default) {
-            supported.push(default);
         }
     }
-    supported
+
+    #[test]
+    fn test_matches_mode_language() {
+        let und = LanguageIdentifier::UND;
+        let und_latn = langid!("und-Latn");
+        let en = langid!("en");
+        let en_latn = langid!("en-Latn");
+        let de_latn = langid!("de-Latn");
+
+        // Other subtags don't matter when the languages differ.
+        for mode in [RangeMode::Exact, RangeMode::BothRanges] {
+            assert!(!matches_mode(&en_latn, &de_latn, mode));
+            assert!(!matches_mode(&en, &de_latn, mode));
+        }
+
+        // The undetermined language matches any language only as a range.
+        assert!(matches_mode(&und_latn, &de_latn, RangeMode::SelfIsRange));
+        assert!(!matches_mode(&und_latn, &de_latn, RangeMode::OtherIsRange));
+        assert!(matches_mode(&de_latn, &und_latn, RangeMode::OtherIsRange));
+        assert!(matches_mode(&und_latn, &en, RangeMode::BothRanges));
+        assert!(!matches_mode(&und, &und_latn, RangeMode::Exact));
+        assert!(matches_mode(&und, &und_latn, RangeMode::SelfIsRange));
+    }
 }
@@ -0,0 +1,394 @@
+use icu_locid::{
+    subtags::{region, Region},
+    LanguageIdentifier,
+};
+
+use super::{LocaleExpander, TransformResult};
+
+/// UN M.49 numeric codes of the countries of ISO 3166-1 and their alpha-2
+/// codes, which CLDR prefers, sorted by the numeric code.
+///
+/// Codes of regions without an alpha-2 code, like `419` for Latin America,
+/// are not listed and are therefore kept.
+static NUMERIC_REGIONS: &[(Region, Region)] = &[
+    (region!("004"), region!("AF")),
+    (region!("008"), region!("AL")),
+    (region!("010"), region!("AQ")),
+    (region!("012"), region!("DZ")),
+    (region!("016"), region!("AS")),
+    (region!("020"), region!("AD")),
+    (region!("024"), region!("AO")),
+    (region!("028"), region!("AG")),
+    (region!("031"), region!("AZ")),
+    (region!("032"), region!("AR")),
+    (region!("036"), region!("AU")),
+    (region!("040"), region!("AT")),
+    (region!("044"), region!("BS")),
+    (region!("048"), region!("BH")),
+    (region!("050"), region!("BD")),
+    (region!("051"), region!("AM")),
+    (region!("052"), region!("BB")),
+    (region!("056"), region!("BE")),
+    (region!("060"), region!("BM")),
+    (region!("064"), region!("BT")),
+    (region!("068"), region!("BO")),
+    (region!("070"), region!("BA")),
+    (region!("072"), region!("BW")),
+    (region!("074"), region!("BV")),
+    (region!("076"), region!("BR")),
+    (region!("084"), region!("BZ")),
+    (region!("086"), region!("IO")),
+    (region!("090"), region!("SB")),
+    (region!("092"), region!("VG")),
+    (region!("096"), region!("BN")),
+    (region!("100"), region!("BG")),
+    (region!("104"), region!("MM")),
+    (region!("108"), region!("BI")),
+    (region!("112"), region!("BY")),
+    (region!("116"), region!("KH")),
+    (region!("120"), region!("CM")),
+    (region!("124"), region!("CA")),
+    (region!("132"), region!("CV")),
+    (region!("136"), region!("KY")),
+    (region!("140"), region!("CF")),
+    (region!("144"), region!("LK")),
+    (region!("148"), region!("TD")),
+    (region!("152"), region!("CL")),
+    (region!("156"), region!("CN")),
+    (region!("158"), region!("TW")),
+    (region!("162"), region!("CX")),
+    (region!("166"), region!("CC")),
+    (region!("170"), region!("CO")),
+    (region!("174"), region!("KM")),
+    (region!("175"), region!("YT")),
+    (region!("178"), region!("CG")),
+    (region!("180"), region!("CD")),
+    (region!("184"), region!("CK")),
+    (region!("188"), region!("CR")),
+    (region!("191"), region!("HR")),
+    (region!("192"), region!("CU")),
+    (region!("196"), region!("CY")),
+    (region!("203"), region!("CZ")),
+    (region!("204"), region!("BJ")),
+    (region!("208"), region!("DK")),
+    (region!("212"), region!("DM")),
+    (region!("214"), region!("DO")),
+    (region!("218"), region!("EC")),
+    (region!("222"), region!("SV")),
+    (region!("226"), region!("GQ")),
+    (region!("231"), region!("ET")),
+    (region!("232"), region!("ER")),
+    (region!("233"), region!("EE")),
+    (region!("234"), region!("FO")),
+    (region!("238"), region!("FK")),
+    (region!("239"), region!("GS")),
+    (region!("242"), region!("FJ")),
+    (region!("246"), region!("FI")),
+    (region!("248"), region!("AX")),
+    (region!("250"), region!("FR")),
+    (region!("254"), region!("GF")),
+    (region!("258"), region!("PF")),
+    (region!("260"), region!("TF")),
+    (region!("262"), region!("DJ")),
+    (region!("266"), region!("GA")),
+    (region!("268"), region!("GE")),
+    (region!("270"), region!("GM")),
+    (region!("275"), region!("PS")),
+    (region!("276"), region!("DE")),
+    (region!("288"), region!("GH")),
+    (region!("292"), region!("GI")),
+    (region!("296"), region!("KI")),
+    (region!("300"), region!("GR")),
+    (region!("304"), region!("GL")),
+    (region!("308"), region!("GD")),
+    (region!("312"), region!("GP")),
+    (region!("316"), region!("GU")),
+    (region!("320"), region!("GT")),
+    (region!("324"), region!("GN")),
+    (region!("328"), region!("GY")),
+    (region!("332"), region!("HT")),
+    (region!("334"), region!("HM")),
+    (region!("336"), region!("VA")),
+    (region!("340"), region!("HN")),
+    (region!("344"), region!("HK")),
+    (region!("348"), region!("HU")),
+    (region!("352"), region!("IS")),
+    (region!("356"), region!("IN")),
+    (region!("360"), region!("ID")),
+    (region!("364"), region!("IR")),
+    (region!("368"), region!("IQ")),
+    (region!("372"), region!("IE")),
+    (region!("376"), region!("IL")),
+    (region!("380"), region!("IT")),
+    (region!("384"), region!("CI")),
+    (region!("388"), region!("JM")),
+    (region!("392"), region!("JP")),
+    (region!("398"), region!("KZ")),
+    (region!("400"), region!("JO")),
+    (region!("404"), region!("KE")),
+    (region!("408"), region!("KP")),
+    (region!("410"), region!("KR")),
+    (region!("414"), region!("KW")),
+    (region!("417"), region!("KG")),
+    (region!("418"), region!("LA")),
+    (region!("422"), region!("LB")),
+    (region!("426"), region!("LS")),
+    (region!("428"), region!("LV")),
+    (region!("430"), region!("LR")),
+    (region!("434"), region!("LY")),
+    (region!("438"), region!("LI")),
+    (region!("440"), region!("LT")),
+    (region!("442"), region!("LU")),
+    (region!("446"), region!("MO")),
+    (region!("450"), region!("MG")),
+    (region!("454"), region!("MW")),
+    (region!("458"), region!("MY")),
+    (region!("462"), region!("MV")),
+    (region!("466"), region!("ML")),
+    (region!("470"), region!("MT")),
+    (region!("474"), region!("MQ")),
+    (region!("478"), region!("MR")),
+    (region!("480"), region!("MU")),
+    (region!("484"), region!("MX")),
+    (region!("492"), region!("MC")),
+    (region!("496"), region!("MN")),
+    (region!("498"), region!("MD")),
+    (region!("499"), region!("ME")),
+    (region!("500"), region!("MS")),
+    (region!("504"), region!("MA")),
+    (region!("508"), region!("MZ")),
+    (region!("512"), region!("OM")),
+    (region!("516"), region!("NA")),
+    (region!("520"), region!("NR")),
+    (region!("524"), region!("NP")),
+    (region!("528"), region!("NL")),
+    (region!("531"), region!("CW")),
+    (region!("533"), region!("AW")),
+    (region!("534"), region!("SX")),
+    (region!("535"), region!("BQ")),
+    (region!("540"), region!("NC")),
+    (region!("548"), region!("VU")),
+    (region!("554"), region!("NZ")),
+    (region!("558"), region!("NI")),
+    (region!("562"), region!("NE")),
+    (region!("566"), region!("NG")),
+    (region!("570"), region!("NU")),
+    (region!("574"), region!("NF")),
+    (region!("578"), region!("NO")),
+    (region!("580"), region!("MP")),
+    (region!("581"), region!("UM")),
+    (region!("583"), region!("FM")),
+    (region!("584"), region!("MH")),
+    (region!("585"), region!("PW")),
+    (region!("586"), region!("PK")),
+    (region!("591"), region!("PA")),
+    (region!("598"), region!("PG")),
+    (region!("600"), region!("PY")),
+    (region!("604"), region!("PE")),
+    (region!("608"), region!("PH")),
+    (region!("612"), region!("PN")),
+    (region!("616"), region!("PL")),
+    (region!("620"), region!("PT")),
+    (region!("624"), region!("GW")),
+    (region!("626"), region!("TL")),
+    (region!("630"), region!("PR")),
+    (region!("634"), region!("QA")),
+    (region!("638"), region!("RE")),
+    (region!("642"), region!("RO")),
+    (region!("643"), region!("RU")),
+    (region!("646"), region!("RW")),
+    (region!("652"), region!("BL")),
+    (region!("654"), region!("SH")),
+    (region!("659"), region!("KN")),
+    (region!("660"), region!("AI")),
+    (region!("662"), region!("LC")),
+    (region!("663"), region!("MF")),
+    (region!("666"), region!("PM")),
+    (region!("670"), region!("VC")),
+    (region!("674"), region!("SM")),
+    (region!("678"), region!("ST")),
+    (region!("682"), region!("SA")),
+    (region!("686"), region!("SN")),
+    (region!("688"), region!("RS")),
+    (region!("690"), region!("SC")),
+    (region!("694"), region!("SL")),
+    (region!("702"), region!("SG")),
+    (region!("703"), region!("SK")),
+    (region!("704"), region!("VN")),
+    (region!("705"), region!("SI")),
+    (region!("706"), region!("SO")),
+    (region!("710"), region!("ZA")),
+    (region!("716"), region!("ZW")),
+    (region!("724"), region!("ES")),
+    (region!("728"), region!("SS")),
+    (region!("729"), region!("SD")),
+    (region!("732"), region!("EH")),
+    (region!("740"), region!("SR")),
+    (region!("744"), region!("SJ")),
+    (region!("748"), region!("SZ")),
+    (region!("752"), region!("SE")),
+    (region!("756"), region!("CH")),
+    (region!("760"), region!("SY")),
+    (region!("762"), region!("TJ")),
+    (region!("764"), region!("TH")),
+    (region!("768"), region!("TG")),
+    (region!("772"), region!("TK")),
+    (region!("776"), region!("TO")),
+    (region!("780"), region!("TT")),
+    (region!("784"), region!("AE")),
+    (region!("788"), region!("TN")),
+    (region!("792"), region!("TR")),
+    (region!("795"), region!("TM")),
+    (region!("796"), region!("TC")),
+    (region!("798"), region!("TV")),
+    (region!("800"), region!("UG")),
+    (region!("804"), region!("UA")),
+    (region!("807"), region!("MK")),
+    (region!("818"), region!("EG")),
+    (region!("826"), region!("GB")),
+    (region!("831"), region!("GG")),
+    (region!("832"), region!("JE")),
+    (region!("833"), region!("IM")),
+    (region!("834"), region!("TZ")),
+    (region!("840"), region!("US")),
+    (region!("850"), region!("VI")),
+    (region!("854"), region!("BF")),
+    (region!("858"), region!("UY")),
+    (region!("860"), region!("UZ")),
+    (region!("862"), region!("VE")),
+    (region!("876"), region!("WF")),
+    (region!("882"), region!("WS")),
+    (region!("887"), region!("YE")),
+    (region!("894"), region!("ZM")),
+];
+
+/// Deprecated region codes and their replacements, based on CLDR's
+/// `territoryAlias` data.
+///
+/// When a region has been split into multiple successors, the first one
+/// is used unless the likely region of the language is one of them.
+static REGION_ALIASES: &[(Region, &[Region])] = &[
+    (
+        region!("AN"),
+        &[region!("CW"), region!("SX"), region!("BQ")],
+    ),
+    (region!("BU"), &[region!("MM")]),
+    (region!("CS"), &[region!("RS"), region!("ME")]),
+    (region!("CT"), &[region!("KI")]),
+    (region!("DD"), &[region!("DE")]),
+    (region!("DY"), &[region!("BJ")]),
+    (region!("FQ"), &[region!("AQ"), region!("TF")]),
+    (region!("FX"), &[region!("FR")]),
+    (region!("HV"), &[region!("BF")]),
+    (region!("JT"), &[region!("UM")]),
+    (region!("MI"), &[region!("UM")]),
+    (region!("NH"), &[region!("VU")]),
+    (region!("NQ"), &[region!("AQ")]),
+    (region!("NT"), &[region!("SA"), region!("IQ")]),
+    (
+        region!("PC"),
+        &[region!("FM"), region!("MH"), region!("MP"), region!("PW")],
+    ),
+    (region!("PU"), &[region!("UM")]),
+    (region!("PZ"), &[region!("PA")]),
+    (region!("QU"), &[region!("EU")]),
+    (region!("RH"), &[region!("ZW")]),
+    (
+        region!("SU"),
+        &[
+            region!("RU"),
+            region!("AM"),
+            region!("AZ"),
+            region!("BY"),
+            region!("EE"),
+            region!("GE"),
+            region!("KZ"),
+            region!("KG"),
+            region!("LV"),
+            region!("LT"),
+            region!("MD"),
+            region!("TJ"),
+            region!("TM"),
+            region!("UA"),
+            region!("UZ"),
+        ],
+    ),
+    (region!("TP"), &[region!("TL")]),
+    (region!("UK"), &[region!("GB")]),
+    (region!("VD"), &[region!("VN")]),
+    (region!("WK"), &[region!("UM")]),
+    (region!("YD"), &[region!("YE")]),
+    (region!("YU"), &[region!("RS"), region!("ME")]),
+    (region!("ZR"), &[region!("CD")]),
+];
+
+/// Returns `true` if the region is numeric with an alpha-2 code, or deprecated.
+pub fn is_alias(region: Region) -> bool {
+    NUMERIC_REGIONS
+        .binary_search_by(|(r, _)| r.cmp(&region))
+        .is_ok()
+        || REGION_ALIASES
+            .binary_search_by(|(r, _)| r.cmp(&region))
+            .is_ok()
+}
+
+/// Replaces a numeric region of the identifier with its alpha-2 code, or a
+/// deprecated region with its successor.
+///
+/// Returns `true` if the region has been replaced.
+pub fn canonicalize_region(input: &mut LanguageIdentifier, lc: &LocaleExpander) -> bool {
+    let Some(region) = input.region else {
+        return false;
+    };
+    if let Ok(idx) = NUMERIC_REGIONS.binary_search_by(|(r, _)| r.cmp(&region)) {
+        input.region = Some(NUMERIC_REGIONS[idx].1);
+        return true;
+    }
+    let Ok(idx) = REGION_ALIASES.binary_search_by(|(r, _)| r.cmp(&region)) else {
+        return false;
+    };
+    let replacements = REGION_ALIASES[idx].1;
+
+    let mut replacement = replacements[0];
+    if replacements.len() > 1 {
+        let mut likely: LanguageIdentifier = input.language.into();
+        if lc.maximize(&mut likely) == TransformResult::Modified {
+            if let Some(likely_region) = likely.region.filter(|r| replacements.contains(r)) {
+                replacement = likely_region;
+            }
+        }
+    }
+    input.region = Some(replacement);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numeric_regions_sort() {
+        for v in NUMERIC_REGIONS.windows(2) {
+            let (v1, v2) = (v[0], v[1]);
+            assert!(
+                v1.0 < v2.0,
+                "Region \"{}\" is placed after \"{}\"",
+                v1.0,
+                v2.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_region_aliases_sort() {
+        for v in REGION_ALIASES.windows(2) {
+            let (v1, v2) = (v[0], v[1]);
+            assert!(
+                v1.0 < v2.0,
+                "Region \"{}\" is placed after \"{}\"",
+                v1.0,
+                v2.0
+            );
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Negotiates available locales discovered asynchronously, like from a
+//! manifest fetched over the network.
+//!
+//! Negotiation needs the full list of available locales, so the stream is
+//! awaited to completion before negotiating.
+
+use std::future::poll_fn;
+use std::pin::pin;
+
+use futures_core::Stream;
+use icu_locid::LanguageIdentifier;
+
+use crate::available_from_tags;
+use crate::negotiate::{negotiate_languages, NegotiationStrategy};
+
+/// Collects the tags of a stream as available locales, and negotiates them
+/// against `requested`.
+///
+/// Like [`available_from_tags`], tags are parsed leniently, tags which fail to
+/// parse are skipped, and tags which are equal once parsed are kept only once.
+///
+/// # Example:
+///
+/// ```
+/// use fluent_langneg::stream::negotiate_stream;
+/// use fluent_langneg::{convert_vec_str_to_langids_lossy, NegotiationStrategy};
+/// use icu_locid::langid;
+///
+/// async fn supported(
+///     manifest: impl futures_core::Stream<Item = String>,
+/// ) -> Vec<icu_locid::LanguageIdentifier> {
+///     let requested = convert_vec_str_to_langids_lossy(&["de-AT", "en"]);
+///     let default = langid!("en-US");
+///     negotiate_stream(&requested, manifest, Some(&default), NegotiationStrategy::Filtering).await
+/// }
+/// ```
+pub async fn negotiate_stream<R, S>(
+    requested: &[R],
+    available: S,
+    default: Option<&LanguageIdentifier>,
+    strategy: NegotiationStrategy,
+) -> Vec<LanguageIdentifier>
+where
+    R: AsRef<LanguageIdentifier>,
+    S: Stream,
+    S::Item: AsRef<[u8]>,
+{
+    let mut available = pin!(available);
+    let mut tags = vec![];
+    while let Some(tag) = poll_fn(|cx| available.as_mut().poll_next(cx)).await {
+        tags.push(tag);
+    }
+
+    let (available, _) = available_from_tags(tags);
+    let default = default.cloned();
+    negotiate_languages(requested, &available, default.as_ref(), strategy)
+        .into_iter()
+        .cloned()
+        .collect()
+}
@@ -3,11 +3,57 @@ use std::fs;
 use std::fs::File;
 use std::path::Path;
 
+use fluent_langneg::available_from_tags;
+use fluent_langneg::canonical_locales;
+use fluent_langneg::convert_vec_str_to_langids;
 use fluent_langneg::convert_vec_str_to_langids_lossy;
+use fluent_langneg::coverage;
+use fluent_langneg::explain;
+use fluent_langneg::filter_available;
+use fluent_langneg::join_tags;
+use fluent_langneg::langid::{
+    add_likely_subtags, as_parts, canonicalize_aliases, eq_canonical_str, extension, from_parts,
+    grandfathered, has_extension, is_equivalent, language_and_region, language_and_script,
+    language_only, likely_script, matches_ignoring_variants, matches_language_script, maximize,
+    parse_lossy, parse_range, remove_likely_subtags, to_string_with_separator, truncations,
+    with_region, with_script, with_variants, MaximizeDepth,
+};
+use fluent_langneg::match_level;
+use fluent_langneg::matches_range;
+use fluent_langneg::negotiate::AvailablePreference;
+use fluent_langneg::negotiate_best_within;
+use fluent_langneg::negotiate_from_accept_language;
+use fluent_langneg::negotiate_grouped;
 use fluent_langneg::negotiate_languages;
+use fluent_langneg::negotiate_languages_checked;
+use fluent_langneg::negotiate_languages_str;
+use fluent_langneg::negotiate_languages_with_defaults;
+use fluent_langneg::negotiate_map;
+use fluent_langneg::negotiate_one;
+use fluent_langneg::negotiate_pairs;
+use fluent_langneg::negotiate_ranked;
+use fluent_langneg::negotiate_result;
+use fluent_langneg::negotiate_top_n;
+use fluent_langneg::negotiate_with_confidence;
 use fluent_langneg::parse_accepted_languages;
+use fluent_langneg::to_gettext_language_chain;
+use fluent_langneg::unserved;
+use fluent_langneg::validate_available;
+use fluent_langneg::AvailabilityWarning;
+use fluent_langneg::CanonicalLocale;
+use fluent_langneg::InvalidTag;
+use fluent_langneg::LangugeIdentifierParserError;
 use fluent_langneg::NegotiationStrategy;
-use icu_locid::{langid, locale, LanguageIdentifier, Locale};
+use fluent_langneg::{convert_vec_str_to_locales, convert_vec_str_to_locales_lossy};
+use fluent_langneg::{
+    Confidence, DefaultBehavior, DefaultPosition, MacrolanguageFallback, MatchKind, MatchedLocale,
+    Negotiated, Negotiator, NegotiatorConfig,
+};
+use icu_locid::{
+    langid, locale,
+    subtags::{language, region, script},
+    LanguageIdentifier, Locale,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -210,3 +256,3395 @@ fn locale_matching() {
         &[&loc_en, &loc_de],
     );
 }
+
+#[test]
+fn accepted_languages_as_ranges() {
+    let requested = parse_accepted_languages("en-US;q=0.8, en");
+    let available = convert_vec_str_to_langids_lossy(["en-GB", "de"]);
+
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+        &[&langid!("en-GB")]
+    );
+
+    // A bare language matches its regional variants.
+    let requested = parse_accepted_languages("en");
+    let available = convert_vec_str_to_langids_lossy(["en-US"]);
+    for strategy in [
+        NegotiationStrategy::Filtering,
+        NegotiationStrategy::Matching,
+        NegotiationStrategy::Lookup,
+    ] {
+        assert_eq!(
+            negotiate_languages(&requested, &available, None, strategy),
+            &[&langid!("en-US")]
+        );
+    }
+    assert_eq!(
+        match_level(&requested[0], &available[0]),
+        Some(MatchKind::LikelySubtags)
+    );
+
+    let requested = parse_accepted_languages("ja");
+    let available = convert_vec_str_to_langids_lossy(["ja-JP"]);
+
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+        &[&langid!("ja-JP")]
+    );
+}
+
+#[test]
+fn negotiate_accept_language_header() {
+    let available = convert_vec_str_to_langids_lossy(["en-US", "de", "fr"]);
+
+    assert_eq!(
+        negotiate_from_accept_language(
+            "de-AT;q=0.9, fr;q=0.8",
+            &available,
+            Some(&available[0]),
+            NegotiationStrategy::Filtering
+        ),
+        &[&available[1], &available[2], &available[0]]
+    );
+
+    // Output of other Accept-Language parsers is a list of strings.
+    let parsed: Vec<String> = vec!["fr-CA".into(), "de".into()];
+    let requested = convert_vec_str_to_langids_lossy(&parsed);
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Lookup),
+        &[&available[2]]
+    );
+}
+
+#[test]
+fn tag_matches_range() {
+    assert!(matches_range("en-US", "en"));
+    assert!(matches_range("en-US", "en-US"));
+    assert!(matches_range("en_us", "EN"));
+    assert!(!matches_range("en", "en-US"));
+    assert!(!matches_range("en-GB", "en-US"));
+    assert!(matches_range("de-1901-1996", "de-1996"));
+    assert!(!matches_range("de-1901", "de-1901-1996"));
+    assert!(matches_range("pl", "und"));
+    assert!(!matches_range("sr-Latn", "sr-Cyrl"));
+    assert!(!matches_range("en-@#$", "en"));
+    assert!(!matches_range("en", ""));
+}
+
+#[test]
+fn pair_match_level() {
+    let cases = [
+        ("en-US", "en-US", Some(MatchKind::Exact)),
+        ("en_us", "EN-US", Some(MatchKind::Exact)),
+        ("en-US", "en", Some(MatchKind::AvailableRange)),
+        ("en", "en-US", Some(MatchKind::LikelySubtags)),
+        ("de-1901", "de-1901-1996", Some(MatchKind::VariantRange)),
+        (
+            "en-CA",
+            "en-US",
+            Some(MatchKind::LikelySubtagsWithoutRegion),
+        ),
+        ("en-US", "en-GB", Some(MatchKind::RegionRange)),
+        ("sr-CS", "sr-RS", Some(MatchKind::RegionAlias)),
+        ("sr-RS", "sr-YU", Some(MatchKind::RegionAlias)),
+        ("sr-Latn-RS", "sr-CS", Some(MatchKind::RegionAlias)),
+        ("en", "de", None),
+        ("sr-Cyrl", "sr-Latn", None),
+    ];
+    for (requested, available, kind) in cases {
+        let requested: LanguageIdentifier = requested.parse().unwrap();
+        let available: LanguageIdentifier = available.parse().unwrap();
+        assert_eq!(
+            match_level(&requested, &available),
+            kind,
+            "{} × {}",
+            requested,
+            available
+        );
+    }
+
+    let negotiator = Negotiator::new(NegotiationStrategy::Filtering)
+        .with_equivalences(&[&[language!("nb"), language!("nn")]]);
+    assert_eq!(
+        negotiator.match_level(&langid!("nb"), &langid!("nn")),
+        Some(MatchKind::Equivalent)
+    );
+    assert_eq!(match_level(&langid!("nb"), &langid!("nn")), None);
+}
+
+#[test]
+fn langid_truncations() {
+    assert_eq!(
+        truncations("en-Latn-US-x-foo").collect::<Vec<_>>(),
+        &["en-Latn-US-x-foo", "en-Latn-US", "en-Latn", "en"]
+    );
+    assert_eq!(
+        truncations("de-CH-1996").collect::<Vec<_>>(),
+        &["de-CH-1996", "de-CH", "de"]
+    );
+    assert_eq!(truncations("fr_FR").collect::<Vec<_>>(), &["fr_FR", "fr"]);
+    assert_eq!(truncations("pl").collect::<Vec<_>>(), &["pl"]);
+    assert_eq!(truncations("x-foo").collect::<Vec<_>>(), &["x-foo"]);
+    assert_eq!(truncations("").next(), None);
+}
+
+#[test]
+fn langid_truncations_singletons() {
+    assert_eq!(
+        truncations("en-a-bbb-x-y").collect::<Vec<_>>(),
+        &["en-a-bbb-x-y", "en-a-bbb", "en"]
+    );
+    assert_eq!(
+        truncations("de-DE-u-co-phonebk").collect::<Vec<_>>(),
+        &["de-DE-u-co-phonebk", "de-DE-u-co", "de-DE", "de"]
+    );
+    assert_eq!(
+        truncations("en-a-x-y").collect::<Vec<_>>(),
+        &["en-a-x-y", "en"]
+    );
+    assert_eq!(
+        truncations("zh-Hant-CN-x-private1-private2").collect::<Vec<_>>(),
+        &[
+            "zh-Hant-CN-x-private1-private2",
+            "zh-Hant-CN-x-private1",
+            "zh-Hant-CN",
+            "zh-Hant",
+            "zh"
+        ]
+    );
+    for tag in ["en-a-bbb-x-y", "de-DE-u-co-phonebk", "sr-Latn-t-ja-x-a-b"] {
+        for truncated in truncations(tag).skip(1) {
+            let last = truncated.rsplit('-').next().unwrap();
+            assert!(last.len() > 1, "{} ends with a singleton", truncated);
+        }
+    }
+}
+
+#[test]
+fn negotiator_default_behavior() {
+    let requested = convert_vec_str_to_langids_lossy(["fr", "de"]);
+    let available = convert_vec_str_to_langids_lossy(["de", "en-US", "it"]);
+    let default = langid!("en-US");
+    let unmatched = convert_vec_str_to_langids_lossy(["pl"]);
+
+    let negotiate = |requested: &[LanguageIdentifier], behavior| {
+        Negotiator::new(NegotiationStrategy::Filtering)
+            .with_default_behavior(behavior)
+            .negotiate(requested, &available, Some(&available[1]))
+    };
+
+    assert_eq!(
+        negotiate(&requested, DefaultBehavior::AppendIfMissing),
+        &[&langid!("de"), &default]
+    );
+    assert_eq!(
+        negotiate(&requested, DefaultBehavior::AppendIfEmpty),
+        &[&langid!("de")]
+    );
+    assert_eq!(
+        negotiate(&unmatched, DefaultBehavior::AppendIfEmpty),
+        &[&default]
+    );
+    assert_eq!(
+        negotiate(&requested, DefaultBehavior::Never),
+        &[&langid!("de")]
+    );
+    assert!(negotiate(&unmatched, DefaultBehavior::Never).is_empty());
+
+    let requested = convert_vec_str_to_langids_lossy(["en-US", "de"]);
+    assert_eq!(
+        negotiate(&requested, DefaultBehavior::AppendAlways),
+        &[&langid!("de"), &default]
+    );
+    assert_eq!(
+        negotiate(&requested, DefaultBehavior::AppendIfMissing),
+        &[&default, &langid!("de")]
+    );
+}
+
+#[test]
+fn negotiator_min_acceptable_level() {
+    let requested = convert_vec_str_to_langids_lossy(["en-GB", "fr"]);
+    let available = convert_vec_str_to_langids_lossy(["en-US", "fr-FR", "de"]);
+    let default = &available[2];
+
+    let negotiate = |level| {
+        Negotiator::new(NegotiationStrategy::Filtering)
+            .with_min_acceptable_level(level)
+            .negotiate(&requested, &available, Some(default))
+    };
+
+    assert_eq!(
+        negotiate(MatchKind::RegionRange),
+        &[&langid!("en-US"), &langid!("fr-FR"), default]
+    );
+    assert_eq!(
+        negotiate(MatchKind::LikelySubtags),
+        &[&langid!("fr-FR"), default]
+    );
+    assert_eq!(negotiate(MatchKind::Exact), &[default]);
+
+    assert_eq!(
+        Negotiator::new(NegotiationStrategy::Lookup)
+            .with_min_acceptable_level(MatchKind::LikelySubtags)
+            .negotiate(&requested, &available, Some(default)),
+        &[&langid!("fr-FR")]
+    );
+}
+
+#[test]
+fn locale_transform_extension() {
+    let loc_en_t_ja: Locale = "en-US-t-ja-jp".parse().unwrap();
+    assert_eq!(loc_en_t_ja.to_string(), "en-US-t-ja-jp");
+
+    let loc_en = locale!("en");
+    let loc_de = locale!("de");
+
+    assert_eq!(
+        negotiate_languages(
+            &[&loc_en_t_ja],
+            &[&loc_de, &loc_en],
+            None,
+            NegotiationStrategy::Filtering
+        ),
+        &[&&loc_en],
+    );
+
+    let available = [loc_de, loc_en_t_ja.clone()];
+    let supported = negotiate_languages(
+        &[locale!("en-US")],
+        &available,
+        None,
+        NegotiationStrategy::Filtering,
+    );
+    assert_eq!(supported, &[&loc_en_t_ja]);
+    assert_eq!(supported[0].to_string(), "en-US-t-ja-jp");
+}
+
+#[test]
+fn negotiate_best_within_level() {
+    let requested = convert_vec_str_to_langids_lossy(["fr-CA", "de-AT"]);
+    let available = convert_vec_str_to_langids_lossy(["de-AT", "fr-FR", "fr"]);
+
+    assert_eq!(
+        negotiate_best_within(&requested, &available, MatchKind::Exact),
+        Some(&langid!("de-AT"))
+    );
+    assert_eq!(
+        negotiate_best_within(&requested, &available, MatchKind::AvailableRange),
+        Some(&langid!("fr"))
+    );
+    assert_eq!(
+        negotiate_best_within(&requested, &available, MatchKind::Macrolanguage),
+        Some(&langid!("fr"))
+    );
+
+    let available = convert_vec_str_to_langids_lossy(["fr-BE", "it"]);
+    assert_eq!(
+        negotiate_best_within(&requested, &available, MatchKind::LikelySubtags),
+        None
+    );
+    assert_eq!(
+        negotiate_best_within(&requested, &available, MatchKind::RegionRange),
+        Some(&langid!("fr-BE"))
+    );
+}
+
+#[test]
+fn expected_locales_coverage() {
+    let expected = convert_vec_str_to_langids_lossy(["fr", "fr-CA", "fr-BE", "de", "it"]);
+    let available = convert_vec_str_to_langids_lossy(["fr", "de-AT"]);
+
+    assert_eq!(coverage(&expected, &available, MatchKind::Exact), 0.2);
+    assert_eq!(
+        coverage(&expected, &available, MatchKind::AvailableRange),
+        0.6
+    );
+    assert_eq!(coverage(&expected, &available, MatchKind::RegionRange), 0.8);
+
+    let none: &[LanguageIdentifier] = &[];
+    assert_eq!(coverage(&expected, none, MatchKind::Macrolanguage), 0.0);
+    assert_eq!(coverage(none, &available, MatchKind::Exact), 1.0);
+}
+
+#[test]
+fn posix_variant() {
+    let requested: Vec<LanguageIdentifier> = vec!["en-US-posix".parse().unwrap()];
+    let available = convert_vec_str_to_langids_lossy(["en-US"]);
+
+    let negotiator = Negotiator::new(NegotiationStrategy::Filtering);
+    assert!(negotiator
+        .clone()
+        .with_min_acceptable_level(MatchKind::LikelySubtags)
+        .filter_matches(&requested, &available)
+        .is_empty());
+    assert_eq!(
+        negotiator
+            .with_min_acceptable_level(MatchKind::VariantRange)
+            .filter_matches(&requested, &available),
+        &[&langid!("en-US")]
+    );
+}
+
+#[test]
+fn different_scripts_never_match() {
+    let requested = convert_vec_str_to_langids_lossy(["yue-Hans", "sr-Cyrl-RS"]);
+    let available = convert_vec_str_to_langids_lossy(["zh-Hant", "yue-Hant-HK", "sr-Latn"]);
+
+    for strategy in [
+        NegotiationStrategy::Filtering,
+        NegotiationStrategy::Matching,
+        NegotiationStrategy::Lookup,
+    ] {
+        let negotiator = Negotiator::new(strategy)
+            .with_macrolanguage_fallback(MacrolanguageFallback::Bidirectional)
+            .with_equivalences(&[&[language!("sr"), language!("hr"), language!("bs")]]);
+        assert!(negotiator.filter_matches(&requested, &available).is_empty());
+    }
+}
+
+#[test]
+fn negotiator_consume_matched() {
+    let requested = convert_vec_str_to_langids_lossy(["en-US", "en-GB"]);
+    let available = convert_vec_str_to_langids_lossy(["en-GB", "en-AU"]);
+
+    let negotiate = |strategy, consume_matched| {
+        Negotiator::new(strategy)
+            .with_consume_matched(consume_matched)
+            .negotiate(&requested, &available, None)
+    };
+
+    assert_eq!(
+        negotiate(NegotiationStrategy::Matching, true),
+        &[&langid!("en-GB"), &langid!("en-AU")]
+    );
+    assert_eq!(
+        negotiate(NegotiationStrategy::Matching, false),
+        &[&langid!("en-GB")]
+    );
+    assert_eq!(
+        negotiate(NegotiationStrategy::Filtering, true),
+        &[&langid!("en-GB"), &langid!("en-AU")]
+    );
+    assert_eq!(
+        negotiate(NegotiationStrategy::Filtering, false),
+        &[&langid!("en-GB"), &langid!("en-AU")]
+    );
+
+    let available = convert_vec_str_to_langids_lossy(["en-AU"]);
+    assert_eq!(
+        Negotiator::new(NegotiationStrategy::Filtering)
+            .with_consume_matched(false)
+            .negotiate(&requested, &available, None),
+        &[&langid!("en-AU")]
+    );
+}
+
+#[test]
+fn langid_likely_script() {
+    assert_eq!(likely_script(&langid!("sr")), Some(script!("Cyrl")));
+    assert_eq!(likely_script(&langid!("sr-Latn")), Some(script!("Latn")));
+    assert_eq!(likely_script(&langid!("en")), Some(script!("Latn")));
+
+    #[cfg(feature = "cldr")]
+    assert_eq!(likely_script(&langid!("ja")), Some(script!("Jpan")));
+    #[cfg(not(feature = "cldr"))]
+    assert_eq!(likely_script(&langid!("ja")), None);
+}
+
+#[test]
+fn langid_add_likely_subtags() {
+    let mut langid = langid!("en");
+    assert!(add_likely_subtags(&mut langid));
+    assert_eq!(langid, langid!("en-Latn-US"));
+
+    assert!(!add_likely_subtags(&mut langid));
+    assert_eq!(langid, langid!("en-Latn-US"));
+
+    let mut langid = langid!("az-IR");
+    assert!(add_likely_subtags(&mut langid));
+    assert_eq!(langid, langid!("az-Arab-IR"));
+}
+
+#[test]
+fn langid_remove_likely_subtags() {
+    let minimize = |tag: &str| {
+        let mut langid: LanguageIdentifier = tag.parse().unwrap();
+        let modified = remove_likely_subtags(&mut langid);
+        (langid.to_string(), modified)
+    };
+
+    assert_eq!(minimize("en-Latn-US"), ("en".to_string(), true));
+    assert_eq!(minimize("fr-Latn-FR"), ("fr".to_string(), true));
+    assert_eq!(minimize("az-Arab-IR"), ("az-IR".to_string(), true));
+    assert_eq!(minimize("en-Latn-US-posix"), ("en-posix".to_string(), true));
+    assert_eq!(minimize("en"), ("en".to_string(), false));
+    assert_eq!(minimize("en-GB"), ("en-GB".to_string(), false));
+    assert_eq!(minimize("az-IR"), ("az-IR".to_string(), false));
+}
+
+#[test]
+fn langid_maximize_depth() {
+    let fr = langid!("fr");
+    assert_eq!(maximize(&fr, MaximizeDepth::Script), langid!("fr-Latn"));
+    assert_eq!(maximize(&fr, MaximizeDepth::Region), langid!("fr-FR"));
+    assert_eq!(maximize(&fr, MaximizeDepth::Full), langid!("fr-Latn-FR"));
+
+    let az_ir = langid!("az-IR");
+    assert_eq!(
+        maximize(&az_ir, MaximizeDepth::Script),
+        langid!("az-Arab-IR")
+    );
+    assert_eq!(maximize(&az_ir, MaximizeDepth::Region), az_ir);
+
+    let en_latn = langid!("en-Latn");
+    assert_eq!(maximize(&en_latn, MaximizeDepth::Script), en_latn);
+}
+
+#[test]
+fn langid_from_parts() {
+    assert_eq!(from_parts("en", None, None, &[]), Ok(langid!("en")));
+    assert_eq!(
+        from_parts("EN", Some("latn"), Some("us"), &[]),
+        Ok(langid!("en-Latn-US"))
+    );
+    assert_eq!(
+        from_parts("de", None, Some("DE"), &["1996", "1901"]),
+        "de-DE-1901-1996".parse()
+    );
+
+    assert_eq!(
+        from_parts("e", None, None, &[]),
+        Err(LangugeIdentifierParserError::InvalidLanguage)
+    );
+    assert_eq!(
+        from_parts("en", Some("Lat"), None, &[]),
+        Err(LangugeIdentifierParserError::InvalidSubtag)
+    );
+    assert_eq!(
+        from_parts("en", None, Some("USA"), &[]),
+        Err(LangugeIdentifierParserError::InvalidSubtag)
+    );
+    assert_eq!(
+        from_parts("en", None, None, &["mac"]),
+        Err(LangugeIdentifierParserError::InvalidSubtag)
+    );
+}
+
+#[test]
+fn negotiator_macrolanguage_fallback() {
+    let negotiate = |requested: &[&str], available: &[&str], fallback| {
+        let requested = convert_vec_str_to_langids_lossy(requested);
+        let available = convert_vec_str_to_langids_lossy(available);
+        Negotiator::new(NegotiationStrategy::Filtering)
+            .with_macrolanguage_fallback(fallback)
+            .negotiate(&requested, &available, None)
+            .into_iter()
+            .map(|l| l.to_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        negotiate(
+            &["yue"],
+            &["zh-Hans", "zh-Hant"],
+            MacrolanguageFallback::Disabled
+        ),
+        Vec::<String>::new()
+    );
+    assert_eq!(
+        negotiate(
+            &["yue"],
+            &["zh-Hans", "zh-Hant"],
+            MacrolanguageFallback::ToMacrolanguage
+        ),
+        &["zh-Hant"]
+    );
+    assert_eq!(
+        negotiate(
+            &["nan"],
+            &["de", "zh"],
+            MacrolanguageFallback::ToMacrolanguage
+        ),
+        &["zh"]
+    );
+    assert_eq!(
+        negotiate(
+            &["yue", "de"],
+            &["de", "zh-Hant"],
+            MacrolanguageFallback::ToMacrolanguage
+        ),
+        &["zh-Hant", "de"]
+    );
+    assert_eq!(
+        Negotiator::new(NegotiationStrategy::Lookup)
+            .with_macrolanguage_fallback(MacrolanguageFallback::Bidirectional)
+            .negotiate(
+                &[langid!("zh"), langid!("de")],
+                &[langid!("de"), langid!("yue"), langid!("cmn")],
+                None
+            ),
+        &[&langid!("yue")]
+    );
+
+    assert_eq!(
+        negotiate(
+            &["no"],
+            &["nb", "nn"],
+            MacrolanguageFallback::ToMacrolanguage
+        ),
+        Vec::<String>::new()
+    );
+    assert_eq!(
+        negotiate(&["no"], &["nn", "nb"], MacrolanguageFallback::Bidirectional),
+        &["nn", "nb"]
+    );
+}
+
+#[test]
+fn join_negotiated_tags() {
+    let requested = convert_vec_str_to_langids_lossy(["pl", "fr", "en-US"]);
+    let available = convert_vec_str_to_langids_lossy(["it", "de", "fr", "en-GB", "en-US"]);
+    let supported =
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering);
+    assert_eq!(join_tags(&supported), "fr,en-US,en-GB");
+
+    let supported: Vec<&LanguageIdentifier> = vec![];
+    assert_eq!(join_tags(&supported), "");
+
+    let locales = [locale!("en-US-u-hc-h12"), locale!("de")];
+    assert_eq!(join_tags(&locales), "en-US-u-hc-h12,de");
+}
+
+#[test]
+fn negotiator_equivalences() {
+    let negotiator = Negotiator::new(NegotiationStrategy::Filtering).with_equivalences(&[
+        &[language!("nb"), language!("nn"), language!("no")],
+        &[language!("id"), language!("ms")],
+    ]);
+
+    let requested = convert_vec_str_to_langids_lossy(["nb", "ms-MY"]);
+    let available = convert_vec_str_to_langids_lossy(["id", "de", "nn", "no"]);
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, None),
+        &[&langid!("nn"), &langid!("no"), &langid!("id")]
+    );
+
+    let available = convert_vec_str_to_langids_lossy(["nn", "nb-NO"]);
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, None),
+        &[&langid!("nb-NO"), &langid!("nn")]
+    );
+
+    let available = convert_vec_str_to_langids_lossy(["de", "sv"]);
+    assert!(negotiator
+        .negotiate(&requested, &available, None)
+        .is_empty());
+
+    let available = convert_vec_str_to_langids_lossy(["nn", "id"]);
+    assert!(Negotiator::new(NegotiationStrategy::Filtering)
+        .negotiate(&requested, &available, None)
+        .is_empty());
+
+    let negotiator = Negotiator::new(NegotiationStrategy::Lookup)
+        .with_equivalences(&[&[language!("nb"), language!("nn")]]);
+    let requested = convert_vec_str_to_langids_lossy(["nb", "de"]);
+    let available = convert_vec_str_to_langids_lossy(["de", "nn"]);
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, None),
+        &[&langid!("nn")]
+    );
+}
+
+#[test]
+fn negotiate_top_n_matches() {
+    let requested = convert_vec_str_to_langids_lossy(["de", "fr", "en"]);
+    let available = convert_vec_str_to_langids_lossy(["en-US", "fr-CA", "de-AT", "de-DE", "it"]);
+    let all = negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering);
+    assert_eq!(all.len(), 4);
+
+    for n in 0..6 {
+        assert_eq!(
+            negotiate_top_n(&requested, &available, n, NegotiationStrategy::Filtering),
+            &all[..n.min(all.len())]
+        );
+    }
+    assert_eq!(
+        negotiate_top_n(&requested, &available, 2, NegotiationStrategy::Matching),
+        &[&langid!("de-DE"), &langid!("fr-CA")]
+    );
+    assert_eq!(
+        negotiate_top_n(&requested, &available, 2, NegotiationStrategy::Lookup),
+        &[&langid!("de-DE")]
+    );
+
+    let negotiator = Negotiator::new(NegotiationStrategy::Filtering).with_consume_matched(false);
+    let requested = convert_vec_str_to_langids_lossy(["de-AT", "de", "fr"]);
+    assert_eq!(
+        negotiator.negotiate_top_n(&requested, &available, 3),
+        &[&langid!("de-AT"), &langid!("de-DE"), &langid!("fr-CA")]
+    );
+}
+
+#[test]
+fn locales_conversion() {
+    let requested = convert_vec_str_to_locales(["de-AT-u-hc-h24", "en-US"]).unwrap();
+    let available = convert_vec_str_to_locales_lossy(["pl-x-private", "en", "de", "???"]);
+    assert_eq!(available.len(), 3);
+
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+        &[&locale!("de"), &locale!("en")]
+    );
+
+    assert_eq!(
+        convert_vec_str_to_locales(["en", "???"]),
+        Err(LangugeIdentifierParserError::InvalidLanguage)
+    );
+}
+
+#[test]
+fn negotiate_languages_map() {
+    let requested = convert_vec_str_to_langids_lossy(["de-AT", "pl", "en-US", "en-GB"]);
+    let available = convert_vec_str_to_langids_lossy(["en-US", "de", "de-AT", "it"]);
+
+    let map = |strategy| {
+        negotiate_map(&requested, &available, strategy)
+            .into_iter()
+            .map(|(req, avail)| (req.to_string(), avail.map(|a| a.to_string())))
+            .collect::<Vec<_>>()
+    };
+    let entry = |req: &str, avail: Option<&str>| (req.to_string(), avail.map(String::from));
+
+    assert_eq!(
+        map(NegotiationStrategy::Filtering),
+        &[
+            entry("de-AT", Some("de-AT")),
+            entry("pl", None),
+            entry("en-US", Some("en-US")),
+            entry("en-GB", Some("en-US")),
+        ]
+    );
+    assert_eq!(
+        map(NegotiationStrategy::Lookup),
+        &[
+            entry("de-AT", Some("de-AT")),
+            entry("pl", None),
+            entry("en-US", None),
+            entry("en-GB", None),
+        ]
+    );
+
+    // Available locales are not consumed by earlier requested locales.
+    for consume_matched in [true, false] {
+        let map = Negotiator::new(NegotiationStrategy::Matching)
+            .with_consume_matched(consume_matched)
+            .negotiate_map(&requested, &available);
+        assert_eq!(map[2].1, Some(&langid!("en-US")));
+        assert_eq!(map[3].1, Some(&langid!("en-US")));
+    }
+}
+
+#[test]
+fn canonical_locales_like_intl() {
+    assert_eq!(
+        canonical_locales([
+            "EN-us",
+            "Fr-fR",
+            "en-US",
+            "de-u-CA-Gregory",
+            "DE-U-ca-gregory"
+        ]),
+        Ok(vec![
+            "en-US".to_string(),
+            "fr-FR".to_string(),
+            "de-u-ca-gregory".to_string()
+        ])
+    );
+    assert_eq!(
+        canonical_locales(["sr-latn", "zh-hant-tw"]),
+        Ok(vec!["sr-Latn".to_string(), "zh-Hant-TW".to_string()])
+    );
+    assert_eq!(
+        canonical_locales(["de-1996-1901", "DE-1901-1996", "sl-Rozaj-Biske-1994"]),
+        Ok(vec![
+            "de-1901-1996".to_string(),
+            "sl-1994-biske-rozaj".to_string()
+        ])
+    );
+    assert_eq!(
+        canonical_locales(["ca-ES-VALENCIA", "ca-es-Valencia", "de-FONIPA-1901"]),
+        Ok(vec![
+            "ca-ES-valencia".to_string(),
+            "de-1901-fonipa".to_string()
+        ])
+    );
+    assert_eq!(canonical_locales(Vec::<&str>::new()), Ok(vec![]));
+    assert_eq!(
+        canonical_locales(["en-US", "en-USA", "x"]),
+        Err(LangugeIdentifierParserError::InvalidSubtag)
+    );
+}
+
+#[test]
+fn mixed_case_extensions() {
+    let locales = convert_vec_str_to_locales([
+        "en-U-CA-GREGORY",
+        "DE-T-EN-US-H0-HYBRID",
+        "sr-LATN-rs-U-NU-LATN-X-PRIV",
+    ])
+    .unwrap();
+    assert_eq!(
+        join_tags(&locales),
+        "en-u-ca-gregory,de-t-en-us-h0-hybrid,sr-Latn-RS-u-nu-latn-x-priv"
+    );
+    assert_eq!(locales[0], locale!("en-u-ca-gregory"));
+
+    let requested = convert_vec_str_to_locales(["EN-u-Ca-Gregory"]).unwrap();
+    assert_eq!(
+        negotiate_languages(&requested, &locales, None, NegotiationStrategy::Matching),
+        &[&locales[0]]
+    );
+}
+
+#[test]
+fn available_locales_from_tags() {
+    let listing = vec![
+        "en-US".to_string(),
+        "templates".to_string(),
+        "pl".to_string(),
+        "en_us".to_string(),
+        " sr-Latn ".to_string(),
+        "PL".to_string(),
+    ];
+    let (available, invalid) = available_from_tags(listing);
+    assert_eq!(
+        available,
+        &[langid!("en-US"), langid!("pl"), langid!("sr-Latn")]
+    );
+    assert_eq!(invalid, &["templates".to_string()]);
+
+    let (available, invalid) = available_from_tags(Vec::<&str>::new());
+    assert!(available.is_empty());
+    assert!(invalid.is_empty());
+}
+
+#[test]
+fn negotiate_checked_reports_invalid_tags() {
+    let requested = convert_vec_str_to_langids_lossy(["en-GB", "pl"]);
+    let available = vec!["pl".to_string(), "en-US".to_string(), "en_GB ".to_string()];
+
+    assert_eq!(
+        negotiate_languages_checked(
+            &requested,
+            &available,
+            Some(&available[1]),
+            NegotiationStrategy::Filtering
+        ),
+        Ok(vec![&available[2], &available[1], &available[0]])
+    );
+    assert_eq!(
+        negotiate_languages_checked(
+            &requested,
+            &["pl", "en-US"],
+            Some(&"de"),
+            NegotiationStrategy::Lookup
+        ),
+        Ok(vec![&"en-US"])
+    );
+    assert_eq!(
+        negotiate_languages_checked(
+            &requested,
+            &["pl", "en-UK-", "englsh", "de"],
+            Some(&"d"),
+            NegotiationStrategy::Matching
+        ),
+        Err(vec![
+            InvalidTag {
+                tag: "englsh".to_string(),
+                error: LangugeIdentifierParserError::InvalidLanguage
+            },
+            InvalidTag {
+                tag: "d".to_string(),
+                error: LangugeIdentifierParserError::InvalidLanguage
+            },
+        ])
+    );
+}
+
+#[test]
+fn grandfathered_tags() {
+    assert_eq!(grandfathered("i-klingon"), Some(langid!("tlh")));
+    assert_eq!(grandfathered(" I_Klingon "), Some(langid!("tlh")));
+    assert_eq!(grandfathered("zh-min-nan"), Some(langid!("nan")));
+    assert_eq!(grandfathered("en-GB-oed"), Some(langid!("en-GB-oxendict")));
+    assert_eq!(grandfathered("i-default"), None);
+    assert_eq!(grandfathered("tlh"), None);
+
+    let requested = convert_vec_str_to_langids_lossy(["tlh", "nb"]);
+    assert_eq!(
+        negotiate_languages_checked(
+            &requested,
+            &["en-US", "no-bok", "i-klingon"],
+            Some(&"en-US"),
+            NegotiationStrategy::Filtering
+        ),
+        Ok(vec![&"i-klingon", &"no-bok", &"en-US"])
+    );
+}
+
+#[test]
+fn regular_grandfathered_tags() {
+    // All regular grandfathered tags of the IANA registry with a preferred value.
+    let regular = [
+        ("art-lojban", langid!("jbo")),
+        ("no-bok", langid!("nb")),
+        ("no-nyn", langid!("nn")),
+        ("zh-guoyu", langid!("cmn")),
+        ("zh-hakka", langid!("hak")),
+        ("zh-min-nan", langid!("nan")),
+        ("zh-xiang", langid!("hsn")),
+    ];
+    for (tag, preferred) in &regular {
+        assert_eq!(grandfathered(tag).as_ref(), Some(preferred), "{tag}");
+        assert_eq!(
+            convert_vec_str_to_langids([tag.to_uppercase()]),
+            Ok(vec![preferred.clone()]),
+            "{tag}"
+        );
+        assert_eq!(parse_lossy(tag), *preferred, "{tag}");
+    }
+    assert_eq!(
+        convert_vec_str_to_langids_lossy(regular.iter().map(|(tag, _)| tag)),
+        regular
+            .iter()
+            .map(|(_, preferred)| preferred.clone())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        convert_vec_str_to_locales_lossy(["zh_guoyu"]),
+        &[locale!("cmn")]
+    );
+    assert_eq!(
+        parse_accepted_languages("zh-hakka, zh;q=0.5"),
+        &[langid!("hak"), langid!("zh")]
+    );
+    assert_eq!(
+        canonical_locales(["zh-xiang", "hsn"]),
+        Ok(vec!["hsn".to_string()])
+    );
+
+    // Tags without a preferred value are parsed as usual.
+    assert_eq!(
+        convert_vec_str_to_langids_lossy(["cel-gaulish"]),
+        &[langid!("cel-gaulish")]
+    );
+    assert_eq!(grandfathered("zh-min"), None);
+
+    // Negotiation works against modern available locales.
+    let requested = convert_vec_str_to_langids_lossy(["zh-guoyu", "no-nyn"]);
+    let available = convert_vec_str_to_langids_lossy(["zh", "nn-NO", "cmn-Hans"]);
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+        &[&langid!("cmn-Hans"), &langid!("nn-NO")]
+    );
+}
+
+#[test]
+fn convert_tolerates_whitespace_and_trailing_separators() {
+    assert_eq!(
+        convert_vec_str_to_langids_lossy([" en-US ", "\tde-AT\n", "fr-", "pl_", "it-CH--"]),
+        &[
+            langid!("en-US"),
+            langid!("de-AT"),
+            langid!("fr"),
+            langid!("pl"),
+            langid!("it-CH")
+        ]
+    );
+    assert_eq!(
+        convert_vec_str_to_locales([" en-u-hc-h12- "]),
+        Ok(vec![locale!("en-u-hc-h12")])
+    );
+
+    assert!(convert_vec_str_to_langids_lossy(["", " ", "-", "en--US"]).is_empty());
+}
+
+#[test]
+fn negotiator_default_position() {
+    let available = convert_vec_str_to_langids_lossy(["de", "en-US", "fr"]);
+    let default = &available[1];
+
+    let negotiate = |requested: &[&str], position| {
+        let requested = convert_vec_str_to_langids_lossy(requested);
+        Negotiator::new(NegotiationStrategy::Filtering)
+            .with_default_position(position)
+            .negotiate(&requested, &available, Some(default))
+            .into_iter()
+            .map(|l| l.to_string())
+            .collect::<Vec<_>>()
+    };
+
+    // The default is matched.
+    assert_eq!(
+        negotiate(&["fr", "en-US", "de"], DefaultPosition::Natural),
+        &["fr", "en-US", "de"]
+    );
+    assert_eq!(
+        negotiate(&["fr", "en-US", "de"], DefaultPosition::First),
+        &["en-US", "fr", "de"]
+    );
+    assert_eq!(
+        negotiate(&["fr", "en-US", "de"], DefaultPosition::Last),
+        &["fr", "de", "en-US"]
+    );
+
+    // The default is appended.
+    assert_eq!(
+        negotiate(&["fr", "de"], DefaultPosition::Natural),
+        &["fr", "de", "en-US"]
+    );
+    assert_eq!(
+        negotiate(&["fr", "de"], DefaultPosition::First),
+        &["en-US", "fr", "de"]
+    );
+    assert_eq!(
+        negotiate(&["fr", "de"], DefaultPosition::Last),
+        &["fr", "de", "en-US"]
+    );
+}
+
+#[test]
+fn langid_parse_range() {
+    assert_eq!(parse_range("en-*-US"), Ok(langid!("en-US")));
+    assert_eq!(parse_range("en_*_us"), Ok(langid!("en-US")));
+    assert_eq!(parse_range("*"), Ok(langid!("und")));
+    assert_eq!(parse_range("*-*-CH"), Ok(langid!("und-CH")));
+    assert_eq!(parse_range("de-*-*-1996"), Ok(langid!("de-1996")));
+    assert_eq!(parse_range("sr-Latn"), Ok(langid!("sr-Latn")));
+    assert!(parse_range("").is_err());
+    assert!(parse_range("en-**-US").is_err());
+
+    let available = [
+        parse_range("en-*-US").unwrap(),
+        parse_range("*-*-CH").unwrap(),
+    ];
+    let requested = convert_vec_str_to_langids_lossy(["en-Latn-US", "de-CH"]);
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+        &[&langid!("en-US"), &langid!("und-CH")]
+    );
+
+    let requested = convert_vec_str_to_langids_lossy(["en-US"]);
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Matching),
+        &[&langid!("en-US")]
+    );
+}
+
+#[test]
+fn langid_parse_lossy() {
+    assert_eq!(parse_lossy("en-US"), langid!("en-US"));
+    assert_eq!(parse_lossy("en-US-@#$"), langid!("en-US"));
+    assert_eq!(parse_lossy(" sr-Latn-RS-mac "), langid!("sr-Latn-RS"));
+    assert_eq!(parse_lossy("en-x-private"), langid!("en"));
+    assert_eq!(parse_lossy("de-@#$-AT"), langid!("de"));
+    assert_eq!(parse_lossy("fr-ąółż"), langid!("fr"));
+    assert_eq!(parse_lossy("@#$-US"), langid!("und"));
+    assert_eq!(parse_lossy(""), langid!("und"));
+}
+
+#[test]
+fn non_ascii_tags() {
+    assert_eq!(
+        convert_vec_str_to_langids(["en-Üs"]),
+        Err(LangugeIdentifierParserError::InvalidSubtag)
+    );
+    assert_eq!(
+        convert_vec_str_to_langids(["ｅｎ"]),
+        Err(LangugeIdentifierParserError::InvalidLanguage)
+    );
+    assert_eq!(
+        convert_vec_str_to_locales(["de-AT-ß"]),
+        Err(LangugeIdentifierParserError::InvalidSubtag)
+    );
+
+    let requested = parse_accepted_languages("en-Üs, Ü;q=0.8, de");
+    assert_eq!(requested, &[langid!("de")]);
+
+    let available = convert_vec_str_to_langids_lossy(["en-Üs", "ｅｎ", "de", "en"]);
+    assert_eq!(available, &[langid!("de"), langid!("en")]);
+
+    let requested = convert_vec_str_to_langids_lossy(["en-US", "de"]);
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+        &[&langid!("en"), &langid!("de")]
+    );
+}
+
+#[test]
+fn langid_eq_canonical_str() {
+    let langid = langid!("en-US");
+    assert!(eq_canonical_str(&langid, "en-US"));
+    assert!(eq_canonical_str(&langid, "EN-us"));
+    assert!(eq_canonical_str(&langid, "en_us"));
+    assert!(eq_canonical_str(&langid, " en-US- "));
+    assert!(!eq_canonical_str(&langid, "en"));
+    assert!(!eq_canonical_str(&langid, "en-US-posix"));
+    assert!(!eq_canonical_str(&langid, "en-GB"));
+    assert!(!eq_canonical_str(&langid, "en-Ü"));
+    assert!(!eq_canonical_str(&langid, ""));
+
+    let langid: LanguageIdentifier = "de-1996-1901".parse().unwrap();
+    assert!(eq_canonical_str(&langid, "DE-1901-1996"));
+    assert!(eq_canonical_str(&langid, "de-1996-1901"));
+    assert!(!eq_canonical_str(&langid, "de-1996"));
+
+    assert!(eq_canonical_str(&langid!("und"), "UND"));
+}
+
+#[test]
+fn langid_to_string_with_separator() {
+    let requested = convert_vec_str_to_langids_lossy(["sr_Latn_RS", "de"]);
+    let available = convert_vec_str_to_langids_lossy(["de_AT", "sr_latn_rs"]);
+    let supported =
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering);
+
+    assert_eq!(
+        supported
+            .iter()
+            .map(|l| to_string_with_separator(*l, '_'))
+            .collect::<Vec<_>>(),
+        &["sr_Latn_RS", "de_AT"]
+    );
+    assert_eq!(to_string_with_separator(&langid!("en"), '_'), "en");
+    assert_eq!(
+        to_string_with_separator(&langid!("ca-ES-valencia"), '-'),
+        "ca-ES-valencia"
+    );
+    assert_eq!(
+        to_string_with_separator(&locale!("en-US-u-hc-h12"), '_'),
+        "en_US_u_hc_h12"
+    );
+}
+
+#[test]
+fn unserved_requested() {
+    let requested = convert_vec_str_to_langids_lossy(["pl", "fr-CA", "it", "de", "ja"]);
+    let available = convert_vec_str_to_langids_lossy(["fr", "de-AT", "en-US"]);
+
+    assert_eq!(
+        unserved(&requested, &available, NegotiationStrategy::Filtering),
+        &[&langid!("pl"), &langid!("it"), &langid!("ja")]
+    );
+    assert_eq!(
+        unserved(&requested, &available, NegotiationStrategy::Matching),
+        &[&langid!("pl"), &langid!("it"), &langid!("ja")]
+    );
+    assert_eq!(
+        unserved(&requested, &available, NegotiationStrategy::Lookup),
+        &[
+            &langid!("pl"),
+            &langid!("it"),
+            &langid!("de"),
+            &langid!("ja")
+        ]
+    );
+
+    let available = convert_vec_str_to_langids_lossy(["pl", "fr", "it", "de", "ja"]);
+    assert!(unserved(&requested, &available, NegotiationStrategy::Filtering).is_empty());
+}
+
+#[test]
+fn explain_negotiation() {
+    let requested = convert_vec_str_to_langids_lossy(["pl", "fr", "en-US"]);
+    let available = convert_vec_str_to_langids_lossy(["it", "de", "fr", "en-GB", "en-US"]);
+
+    assert_eq!(
+        explain(
+            &requested,
+            &available,
+            Some(&available[4]),
+            NegotiationStrategy::Filtering
+        ),
+        "requested [pl, fr, en-US] × available [it, de, fr, en-GB, en-US] → \
+         [fr (exact), en-US (exact), en-GB (region-range)]"
+    );
+    assert_eq!(
+        explain(
+            &requested[..1],
+            &available,
+            Some(&available[4]),
+            NegotiationStrategy::Lookup
+        ),
+        "requested [pl] × available [it, de, fr, en-GB, en-US] → [en-US (default)]"
+    );
+    assert_eq!(
+        explain::<LanguageIdentifier, LanguageIdentifier>(
+            &[],
+            &[],
+            None,
+            NegotiationStrategy::Matching
+        ),
+        "requested [] × available [] → []"
+    );
+
+    assert_eq!(MatchKind::LikelySubtags.to_string(), "likely-subtags");
+}
+
+#[test]
+fn filtering_groups_by_requested() {
+    let requested = convert_vec_str_to_langids_lossy(["en-GB", "en", "fr-CA", "en-US"]);
+    let available = convert_vec_str_to_langids_lossy([
+        "en-US", "fr", "en", "en-CA", "fr-CA", "en-GB", "en-AU", "fr-FR",
+    ]);
+
+    for consume_matched in [true, false] {
+        let negotiator =
+            Negotiator::new(NegotiationStrategy::Filtering).with_consume_matched(consume_matched);
+        let pairs = negotiator.negotiate_pairs(&requested, &available, None);
+
+        let ranks: Vec<usize> = pairs
+            .iter()
+            .map(|(req, _)| requested.iter().position(|r| Some(r) == *req).unwrap())
+            .collect();
+        assert!(ranks.windows(2).all(|w| w[0] <= w[1]), "{:?}", ranks);
+    }
+
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+        &[
+            &langid!("en-GB"),
+            &langid!("en"),
+            &langid!("en-US"),
+            &langid!("en-CA"),
+            &langid!("en-AU"),
+            &langid!("fr-CA"),
+            &langid!("fr"),
+            &langid!("fr-FR"),
+        ]
+    );
+}
+
+#[test]
+fn negotiate_requested_pairs() {
+    let requested = convert_vec_str_to_langids_lossy(["de-AT", "fr", "en-GB"]);
+    let available = convert_vec_str_to_langids_lossy(["en-US", "de", "de-DE", "fr-CA"]);
+    let default = &available[0];
+
+    assert_eq!(
+        negotiate_pairs(
+            &requested,
+            &available,
+            Some(default),
+            NegotiationStrategy::Filtering
+        ),
+        &[
+            (Some(&langid!("de-AT")), &langid!("de")),
+            (Some(&langid!("de-AT")), &langid!("de-DE")),
+            (Some(&langid!("fr")), &langid!("fr-CA")),
+            (Some(&langid!("en-GB")), &langid!("en-US")),
+        ]
+    );
+    assert_eq!(
+        negotiate_pairs(
+            &requested,
+            &available,
+            Some(default),
+            NegotiationStrategy::Lookup
+        ),
+        &[(Some(&langid!("de-AT")), &langid!("de"))]
+    );
+
+    let requested = convert_vec_str_to_langids_lossy(["it"]);
+    assert_eq!(
+        negotiate_pairs(
+            &requested,
+            &available,
+            Some(default),
+            NegotiationStrategy::Matching
+        ),
+        &[(None, &langid!("en-US"))]
+    );
+
+    let negotiator = Negotiator::new(NegotiationStrategy::Matching)
+        .with_default_behavior(DefaultBehavior::AppendAlways);
+    let requested = convert_vec_str_to_langids_lossy(["en", "fr"]);
+    assert_eq!(
+        negotiator.negotiate_pairs(&requested, &available, Some(default)),
+        &[
+            (Some(&langid!("fr")), &langid!("fr-CA")),
+            (None, &langid!("en-US")),
+        ]
+    );
+}
+
+#[test]
+fn negotiate_data_version() {
+    let version = fluent_langneg::negotiate::data_version();
+    assert!(version.starts_with(env!("CARGO_PKG_VERSION")));
+    assert_eq!(version.ends_with("+cldr"), cfg!(feature = "cldr"));
+}
+
+#[test]
+fn negotiator_region_priority() {
+    let requested = convert_vec_str_to_langids_lossy(["en-IE", "pt-AO"]);
+    let available = convert_vec_str_to_langids_lossy(["en-AU", "pt-MZ", "en-GB", "en-CA", "pt-PT"]);
+
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+        &[
+            &langid!("en-AU"),
+            &langid!("en-GB"),
+            &langid!("en-CA"),
+            &langid!("pt-MZ"),
+            &langid!("pt-PT")
+        ]
+    );
+
+    let negotiator = |strategy| {
+        Negotiator::new(strategy)
+            .with_region_priority(language!("en"), &[region!("CA"), region!("GB")])
+            .with_region_priority(language!("pt"), &[region!("PT")])
+    };
+    assert_eq!(
+        negotiator(NegotiationStrategy::Filtering).negotiate(&requested, &available, None),
+        &[
+            &langid!("en-CA"),
+            &langid!("en-GB"),
+            &langid!("en-AU"),
+            &langid!("pt-PT"),
+            &langid!("pt-MZ")
+        ]
+    );
+    assert_eq!(
+        negotiator(NegotiationStrategy::Matching).negotiate(&requested, &available, None),
+        &[&langid!("en-CA"), &langid!("pt-PT")]
+    );
+
+    let negotiator = Negotiator::new(NegotiationStrategy::Lookup)
+        .with_region_priority(language!("en"), &[region!("AU"), region!("GB")])
+        .with_paradigm_locales(&[langid!("en-GB")]);
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, None),
+        &[&langid!("en-GB")]
+    );
+}
+
+#[test]
+fn negotiator_sticky_locale() {
+    let requested = convert_vec_str_to_langids_lossy(["en-US", "fr"]);
+    let available = convert_vec_str_to_langids_lossy(["fr", "en-US", "en-GB", "de"]);
+
+    let negotiator = |strategy| Negotiator::new(strategy).with_sticky_locale(langid!("en-GB"));
+    assert_eq!(
+        negotiator(NegotiationStrategy::Filtering).negotiate(&requested, &available, None),
+        &[&langid!("en-GB"), &langid!("en-US"), &langid!("fr")]
+    );
+    assert_eq!(
+        negotiator(NegotiationStrategy::Matching).negotiate(&requested, &available, None),
+        &[&langid!("en-GB"), &langid!("en-US"), &langid!("fr")]
+    );
+    assert_eq!(
+        negotiator(NegotiationStrategy::Lookup).negotiate(&requested, &available, None),
+        &[&langid!("en-GB")]
+    );
+
+    // The sticky locale no longer matches the requested ones.
+    let requested = convert_vec_str_to_langids_lossy(["fr"]);
+    assert_eq!(
+        negotiator(NegotiationStrategy::Lookup).negotiate(
+            &requested,
+            &available,
+            Some(&available[3])
+        ),
+        &[&langid!("fr")]
+    );
+
+    // The sticky locale is not available anymore.
+    let requested = convert_vec_str_to_langids_lossy(["en-US", "fr"]);
+    let available = convert_vec_str_to_langids_lossy(["fr", "en-US"]);
+    assert_eq!(
+        negotiator(NegotiationStrategy::Lookup).negotiate(&requested, &available, None),
+        &[&langid!("en-US")]
+    );
+
+    // Matches worse than the minimum level don't keep the sticky locale.
+    let available = convert_vec_str_to_langids_lossy(["fr", "en-US", "en-GB"]);
+    let negotiator = Negotiator::new(NegotiationStrategy::Lookup)
+        .with_sticky_locale(langid!("en-GB"))
+        .with_min_acceptable_level(MatchKind::AvailableRange);
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, None),
+        &[&langid!("en-US")]
+    );
+}
+
+#[test]
+fn negotiator_paradigm_locales() {
+    let requested = convert_vec_str_to_langids_lossy(["en-IN"]);
+    let available = convert_vec_str_to_langids_lossy(["en-001", "en-GB", "en-US"]);
+
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+        &[&langid!("en-US"), &langid!("en-001"), &langid!("en-GB")]
+    );
+
+    let negotiator = |strategy| {
+        Negotiator::new(strategy).with_paradigm_locales(&[langid!("en-GB"), langid!("en-US")])
+    };
+    assert_eq!(
+        negotiator(NegotiationStrategy::Filtering).negotiate(&requested, &available, None),
+        &[&langid!("en-US"), &langid!("en-GB"), &langid!("en-001")]
+    );
+
+    let available = convert_vec_str_to_langids_lossy(["en-001", "en-AU", "en-GB"]);
+    assert_eq!(
+        negotiator(NegotiationStrategy::Matching).negotiate(&requested, &available, None),
+        &[&langid!("en-GB")]
+    );
+    assert_eq!(
+        negotiator(NegotiationStrategy::Lookup).negotiate(&requested, &available, None),
+        &[&langid!("en-GB")]
+    );
+}
+
+#[test]
+fn negotiate_multiple_defaults() {
+    let requested = convert_vec_str_to_langids_lossy(["de", "en-US"]);
+    let available = convert_vec_str_to_langids_lossy(["fr", "de-AT", "en-US", "it"]);
+    let defaults = [&available[3], &available[2], &available[0]];
+
+    assert_eq!(
+        negotiate_languages_with_defaults(
+            &requested,
+            &available,
+            &defaults,
+            NegotiationStrategy::Filtering
+        ),
+        &[
+            &langid!("de-AT"),
+            &langid!("en-US"),
+            &langid!("it"),
+            &langid!("fr")
+        ]
+    );
+    assert_eq!(
+        negotiate_languages_with_defaults(
+            &requested,
+            &available,
+            &defaults,
+            NegotiationStrategy::Matching
+        ),
+        &[
+            &langid!("de-AT"),
+            &langid!("en-US"),
+            &langid!("it"),
+            &langid!("fr")
+        ]
+    );
+    assert_eq!(
+        negotiate_languages_with_defaults(
+            &requested,
+            &available,
+            &defaults,
+            NegotiationStrategy::Lookup
+        ),
+        &[&langid!("de-AT")]
+    );
+
+    // Lookup falls back to the first default only.
+    let requested = convert_vec_str_to_langids_lossy(["ja"]);
+    assert_eq!(
+        negotiate_languages_with_defaults(
+            &requested,
+            &available,
+            &defaults,
+            NegotiationStrategy::Lookup
+        ),
+        &[&langid!("it")]
+    );
+    assert_eq!(
+        negotiate_languages_with_defaults(&requested, &available, &[], NegotiationStrategy::Lookup),
+        &[] as &[&LanguageIdentifier]
+    );
+
+    // Defaults are kept in order and moved as a block.
+    let requested = convert_vec_str_to_langids_lossy(["en-US", "de"]);
+    let negotiator = Negotiator::new(NegotiationStrategy::Filtering)
+        .with_default_behavior(DefaultBehavior::AppendAlways)
+        .with_default_position(DefaultPosition::First);
+    assert_eq!(
+        negotiator.negotiate_with_defaults(&requested, &available, &defaults),
+        &[
+            &langid!("it"),
+            &langid!("en-US"),
+            &langid!("fr"),
+            &langid!("de-AT")
+        ]
+    );
+}
+
+#[test]
+fn negotiator_bare_language_matches_all_regions() {
+    let requested = convert_vec_str_to_langids_lossy(["en", "fr-CA"]);
+    let available =
+        convert_vec_str_to_langids_lossy(["en-GB", "en", "en-US", "en-Cyrl-US", "fr-FR", "fr"]);
+
+    // By default, a bare language matches its likely region first.
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Lookup),
+        &[&langid!("en")]
+    );
+
+    let negotiator = |strategy, matches_all_regions| {
+        Negotiator::new(strategy).with_bare_language_matches_all_regions(matches_all_regions)
+    };
+
+    assert_eq!(
+        negotiator(NegotiationStrategy::Filtering, true).negotiate(&requested, &available, None),
+        &[
+            &langid!("en"),
+            &langid!("en-GB"),
+            &langid!("en-US"),
+            &langid!("fr"),
+            &langid!("fr-FR")
+        ]
+    );
+    assert_eq!(
+        negotiator(NegotiationStrategy::Filtering, false).negotiate(&requested, &available, None),
+        &[
+            &langid!("en"),
+            &langid!("en-US"),
+            &langid!("fr"),
+            &langid!("fr-FR")
+        ]
+    );
+
+    let available = convert_vec_str_to_langids_lossy(["de", "en-GB", "en-US"]);
+    assert_eq!(
+        negotiator(NegotiationStrategy::Lookup, true).negotiate(&requested, &available, None),
+        &[&langid!("en-GB")]
+    );
+    assert_eq!(
+        negotiator(NegotiationStrategy::Lookup, false).negotiate(&requested, &available, None),
+        &[&langid!("en-US")]
+    );
+    assert_eq!(
+        negotiator(NegotiationStrategy::Matching, true).negotiate(&requested, &available, None),
+        &[&langid!("en-GB")]
+    );
+}
+
+#[test]
+fn negotiate_from_comma_separated_str() {
+    let available = ["it", "fr", "en-GB", "en_US", "not a tag"];
+
+    assert_eq!(
+        negotiate_languages_str(
+            "pl,fr,en-US",
+            &available,
+            None,
+            NegotiationStrategy::Filtering
+        ),
+        &["fr", "en_US", "en-GB"]
+    );
+    assert_eq!(
+        negotiate_languages_str(
+            "  , pl ,\tfr-CA,,en-US,",
+            &available,
+            None,
+            NegotiationStrategy::Lookup
+        ),
+        &["fr"]
+    );
+    assert_eq!(
+        negotiate_languages_str("", &available, Some("it"), NegotiationStrategy::Matching),
+        &["it"]
+    );
+    assert_eq!(
+        negotiate_languages_str(
+            "xx-@@,de",
+            &available,
+            Some("en-GB"),
+            NegotiationStrategy::Lookup
+        ),
+        &["en-GB"]
+    );
+    assert_eq!(
+        negotiate_languages_str("de", &available, Some("@@"), NegotiationStrategy::Lookup),
+        &[] as &[&str]
+    );
+}
+
+#[test]
+fn langid_is_equivalent() {
+    let pairs = [
+        ("iw-IL", "he-IL"),
+        ("in", "id"),
+        ("ji", "yi"),
+        ("jw-ID", "jv-ID"),
+        ("mo-MD", "ro-MD"),
+        ("de-DD", "de-DE"),
+        ("sr-Latn-YU", "sr-Latn-RS"),
+        ("iw-Hebr-IL", "he-Hebr-IL"),
+        ("en-UK", "en-GB"),
+        ("EN-us", "en-US"),
+    ];
+    for (tag1, tag2) in pairs {
+        let langid1: LanguageIdentifier = tag1.parse().unwrap();
+        let langid2: LanguageIdentifier = tag2.parse().unwrap();
+        assert!(is_equivalent(&langid1, &langid2), "{tag1} and {tag2}");
+        assert!(is_equivalent(&langid2, &langid1), "{tag2} and {tag1}");
+    }
+
+    assert!(!is_equivalent(&langid!("iw"), &langid!("he-IL")));
+    assert!(!is_equivalent(&langid!("en-US"), &langid!("en-Latn-US")));
+    assert!(!is_equivalent(&langid!("sr-Latn"), &langid!("sr-Cyrl")));
+    assert!(is_equivalent(&locale!("iw-IL").id, &locale!("he-IL").id));
+
+    let mut langid = langid!("he-IL");
+    assert!(!canonicalize_aliases(&mut langid));
+    assert_eq!(langid, langid!("he-IL"));
+}
+
+#[test]
+fn negotiator_fallback_script() {
+    // `qaa` is reserved for local use, so there is no likely subtags data for it.
+    let requested = convert_vec_str_to_langids_lossy(["qaa", "qab-DE"]);
+    let available = convert_vec_str_to_langids_lossy(["qaa-Cyrl", "qaa-Latn", "qab-Cyrl-DE"]);
+
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Lookup),
+        &[&langid!("qaa-Cyrl")]
+    );
+
+    let negotiator = |strategy| Negotiator::new(strategy).with_fallback_script(script!("Latn"));
+    assert_eq!(
+        negotiator(NegotiationStrategy::Lookup).negotiate(&requested, &available, None),
+        &[&langid!("qaa-Latn")]
+    );
+    assert_eq!(
+        negotiator(NegotiationStrategy::Filtering).negotiate(&requested, &available, None),
+        &[
+            &langid!("qaa-Latn"),
+            &langid!("qaa-Cyrl"),
+            &langid!("qab-Cyrl-DE")
+        ]
+    );
+    assert_eq!(
+        negotiator(NegotiationStrategy::Filtering).match_level(&requested[0], &available[1]),
+        Some(MatchKind::LikelySubtags)
+    );
+
+    // Languages with likely subtags data are not affected.
+    let requested = convert_vec_str_to_langids_lossy(["sr"]);
+    let available = convert_vec_str_to_langids_lossy(["sr-Latn", "sr-Cyrl"]);
+    assert_eq!(
+        negotiator(NegotiationStrategy::Lookup).negotiate(&requested, &available, None),
+        &[&langid!("sr-Cyrl")]
+    );
+}
+
+#[test]
+fn negotiate_confidence() {
+    let available = convert_vec_str_to_langids_lossy(["en-US", "en-GB", "de", "fr-CA", "it"]);
+    let default = Some(&available[4]);
+    let confidence = |requested: &[&str], strategy| {
+        let requested = convert_vec_str_to_langids_lossy(requested);
+        negotiate_with_confidence(&requested, &available, default, strategy).1
+    };
+
+    // Further matches of the same requested locale don't lower the confidence.
+    assert_eq!(
+        confidence(&["en-US", "de"], NegotiationStrategy::Filtering),
+        Confidence::Exact
+    );
+    assert_eq!(
+        confidence(&["en-US", "de-AT"], NegotiationStrategy::Filtering),
+        Confidence::Good
+    );
+    assert_eq!(
+        confidence(&["en", "de"], NegotiationStrategy::Matching),
+        Confidence::Good
+    );
+    assert_eq!(
+        confidence(&["en-US", "fr-FR"], NegotiationStrategy::Filtering),
+        Confidence::Fuzzy
+    );
+    assert_eq!(
+        confidence(&["fr", "pl"], NegotiationStrategy::Lookup),
+        Confidence::Fuzzy
+    );
+    assert_eq!(
+        confidence(&["pl", "ja"], NegotiationStrategy::Filtering),
+        Confidence::DefaultOnly
+    );
+    assert_eq!(
+        confidence(&[], NegotiationStrategy::Lookup),
+        Confidence::DefaultOnly
+    );
+
+    // Requested locales which are not matched don't lower the confidence.
+    let requested = convert_vec_str_to_langids_lossy(["pl", "en-GB"]);
+    assert_eq!(
+        negotiate_with_confidence(&requested, &available, default, NegotiationStrategy::Lookup),
+        (vec![&langid!("en-GB")], Confidence::Exact)
+    );
+    assert_eq!(
+        negotiate_with_confidence(
+            &[langid!("pl")],
+            &available,
+            default,
+            NegotiationStrategy::Lookup
+        ),
+        (vec![&langid!("it")], Confidence::DefaultOnly)
+    );
+}
+
+#[test]
+fn langid_matches_ignoring_variants() {
+    let pairs = [
+        ("de-DE-1901", "de-DE", true),
+        ("de-DE-1901", "de-DE-1996", true),
+        ("sl-rozaj-biske", "SL", true),
+        ("en-US-posix", "en-US", true),
+        ("de-DE", "de-AT", false),
+        ("de-DE-1901", "de", false),
+        ("sr-Latn-RS", "sr-RS", false),
+        ("sr-Latn-RS", "sr-Cyrl-RS-1901", false),
+    ];
+    for (tag1, tag2, expected) in pairs {
+        let langid1: LanguageIdentifier = tag1.parse().unwrap();
+        let langid2: LanguageIdentifier = tag2.parse().unwrap();
+        assert_eq!(
+            matches_ignoring_variants(&langid1, &langid2),
+            expected,
+            "{tag1} and {tag2}"
+        );
+        assert_eq!(
+            matches_ignoring_variants(&langid2, &langid1),
+            expected,
+            "{tag2} and {tag1}"
+        );
+    }
+    assert!(matches_ignoring_variants(
+        &locale!("de-DE-1901-u-co-phonebk").id,
+        &locale!("de-DE").id
+    ));
+}
+
+#[test]
+fn langid_matches_language_script() {
+    let pairs = [
+        ("en-US", "en-GB", true),
+        ("en-US", "en", true),
+        ("de-DE-1901", "de-AT", true),
+        ("sr-Latn-RS", "sr-Latn-ME", true),
+        ("und-US", "und-FR", true),
+        ("en-US", "fr-FR", false),
+        ("sr-Latn-RS", "sr-Cyrl-RS", false),
+        ("sr-RS", "sr-Cyrl-RS", false),
+        ("und-US", "en-US", false),
+    ];
+    for (tag1, tag2, expected) in pairs {
+        let langid1: LanguageIdentifier = tag1.parse().unwrap();
+        let langid2: LanguageIdentifier = tag2.parse().unwrap();
+        assert_eq!(
+            matches_language_script(&langid1, &langid2),
+            expected,
+            "{tag1} and {tag2}"
+        );
+        assert_eq!(
+            matches_language_script(&langid2, &langid1),
+            expected,
+            "{tag2} and {tag1}"
+        );
+    }
+    assert!(matches_language_script(
+        &locale!("en-US-u-ca-buddhist").id,
+        &locale!("en-GB").id
+    ));
+}
+
+#[test]
+fn filter_available_for_range() {
+    let available = [
+        "en",
+        "en-GB",
+        "de-DE",
+        "en-Latn-US",
+        "en_US",
+        "en-US-posix",
+        "sr-Cyrl-RS",
+        "@@",
+        "de-DE-1901",
+    ];
+
+    assert_eq!(
+        filter_available("en", &available),
+        &["en", "en-GB", "en-Latn-US", "en_US"]
+    );
+    assert_eq!(
+        filter_available("EN-us", &available),
+        &["en-Latn-US", "en_US"]
+    );
+    assert_eq!(
+        filter_available("en-*-US", &available),
+        &["en-Latn-US", "en_US"]
+    );
+    assert_eq!(
+        filter_available("de-DE", &available),
+        &["de-DE", "de-DE-1901"]
+    );
+    assert_eq!(filter_available("de-DE-1901", &available), &["de-DE-1901"]);
+    assert_eq!(
+        filter_available("en-US-posix", &available),
+        &["en-US-posix"]
+    );
+    assert_eq!(filter_available("*-RS", &available), &["sr-Cyrl-RS"]);
+    assert_eq!(filter_available("und-Cyrl", &available), &["sr-Cyrl-RS"]);
+    assert_eq!(filter_available("*", &available).len(), 7);
+
+    // No likely subtags are used.
+    assert!(filter_available("en-Latn", &available).contains(&"en-Latn-US"));
+    assert!(!filter_available("en-Latn", &available).contains(&"en-GB"));
+    assert_eq!(filter_available("en-AU", &available), &[] as &[&str]);
+    assert_eq!(filter_available("@@", &available), &[] as &[&str]);
+}
+
+#[test]
+fn negotiator_without_fuzzy_steps() {
+    let requested = convert_vec_str_to_langids_lossy(["en-AU", "sr-YU", "de", "fr"]);
+    let available = convert_vec_str_to_langids_lossy(["en-GB", "sr-RS", "de-AT", "de", "fr-FR"]);
+
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+        &[
+            &langid!("en-GB"),
+            &langid!("sr-RS"),
+            &langid!("de"),
+            &langid!("de-AT"),
+            &langid!("fr-FR")
+        ]
+    );
+
+    let negotiator =
+        |strategy| Negotiator::new(strategy).with_min_acceptable_level(MatchKind::AvailableRange);
+    assert_eq!(
+        negotiator(NegotiationStrategy::Filtering).negotiate(&requested, &available, None),
+        &[&langid!("de")]
+    );
+    assert_eq!(
+        negotiator(NegotiationStrategy::Lookup).negotiate(&requested, &available, None),
+        &[&langid!("de")]
+    );
+
+    // Replaced deprecated regions are one step further.
+    assert_eq!(
+        Negotiator::new(NegotiationStrategy::Filtering)
+            .with_min_acceptable_level(MatchKind::RegionAlias)
+            .negotiate(&requested, &available, None),
+        &[&langid!("sr-RS"), &langid!("de")]
+    );
+}
+
+#[test]
+fn negotiator_config_round_trip() {
+    let negotiator = Negotiator::new(NegotiationStrategy::Lookup)
+        .with_default_position(DefaultPosition::First)
+        .with_min_acceptable_level(MatchKind::RegionRange)
+        .with_consume_matched(false)
+        .with_macrolanguage_fallback(MacrolanguageFallback::ToMacrolanguage)
+        .with_equivalences(&[&[language!("nb"), language!("nn")]])
+        .with_paradigm_locales(&[langid!("en-GB")])
+        .with_region_priority(language!("pt"), &[region!("PT"), region!("BR")])
+        .with_sticky_locale(langid!("en-US"))
+        .with_bare_language_matches_all_regions(true)
+        .with_fallback_script(script!("Latn"))
+        .with_assume_language(language!("pt"))
+        .with_max_comparisons(1000)
+        .with_excluded_locales(&[langid!("pt-PT")])
+        .with_script_fallback(language!("mn"), script!("Mong"), script!("Cyrl"))
+        .with_include_unmatched(true)
+        .with_available_weights(std::collections::HashMap::from([(
+            "pt_BR".to_string(),
+            0.5,
+        )]))
+        .with_available_preference(AvailablePreference::LastWins);
+
+    let config = negotiator.to_config();
+    assert_eq!(config.strategy, NegotiationStrategy::Lookup);
+    assert_eq!(
+        config.default_behavior,
+        Some(DefaultBehavior::AppendIfEmpty)
+    );
+    assert_eq!(Negotiator::from_config(config.clone()).to_config(), config);
+
+    let requested = convert_vec_str_to_langids_lossy(["nb-NO", "pt-AO"]);
+    let available = convert_vec_str_to_langids_lossy(["pt-BR", "nn-NO", "pt-PT"]);
+    assert_eq!(
+        Negotiator::from_config(config).negotiate(&requested, &available, None),
+        negotiator.negotiate(&requested, &available, None)
+    );
+
+    // The default behavior follows the strategy unless it is set.
+    let config = NegotiatorConfig {
+        strategy: NegotiationStrategy::Lookup,
+        ..Default::default()
+    };
+    assert_eq!(
+        Negotiator::from_config(config).to_config().default_behavior,
+        Some(DefaultBehavior::AppendIfEmpty)
+    );
+    assert_eq!(
+        Negotiator::from_config(NegotiatorConfig::default()).to_config(),
+        Negotiator::new(NegotiationStrategy::Filtering).to_config()
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn negotiator_config_serde() {
+    let config: NegotiatorConfig = serde_json::from_str(
+        r#"{
+            "strategy": "lookup",
+            "min_acceptable_level": "region-range",
+            "equivalences": [["nb", "nn", "no"]],
+            "paradigm_locales": ["en-GB", "es-419"],
+            "region_priority": [["pt", ["PT", "BR"]]],
+            "fallback_script": "Latn"
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(config.strategy, NegotiationStrategy::Lookup);
+    assert_eq!(config.default_behavior, None);
+    assert_eq!(config.min_acceptable_level, Some(MatchKind::RegionRange));
+    assert!(config.consume_matched);
+    assert_eq!(
+        config.paradigm_locales,
+        &[langid!("en-GB"), langid!("es-419")]
+    );
+    assert_eq!(
+        config.region_priority,
+        &[(language!("pt"), vec![region!("PT"), region!("BR")])]
+    );
+
+    let config = Negotiator::from_config(config).to_config();
+    let json = serde_json::to_string(&config).unwrap();
+    assert!(json.contains(r#""default_behavior":"append-if-empty""#));
+    assert_eq!(
+        serde_json::from_str::<NegotiatorConfig>(&json).unwrap(),
+        config
+    );
+
+    assert!(serde_json::from_str::<NegotiatorConfig>(r#"{"strategy": "best"}"#).is_err());
+    assert!(serde_json::from_str::<NegotiatorConfig>(r#"{"paradigm_locales": ["en-"]}"#).is_err());
+}
+
+#[test]
+fn negotiate_chinese_scripts_by_region() {
+    let available = convert_vec_str_to_langids_lossy(["zh-Hans", "zh-Hant"]);
+    for (requested, expected) in [
+        ("zh-TW", langid!("zh-Hant")),
+        ("zh-HK", langid!("zh-Hant")),
+        ("zh-MO", langid!("zh-Hant")),
+        ("zh-CN", langid!("zh-Hans")),
+        ("zh-SG", langid!("zh-Hans")),
+        ("zh", langid!("zh-Hans")),
+    ] {
+        let requested = convert_vec_str_to_langids_lossy([requested]);
+        assert_eq!(
+            negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+            &[&expected],
+            "{}",
+            requested[0]
+        );
+        assert_eq!(
+            match_level(&requested[0], &expected),
+            Some(MatchKind::LikelySubtags)
+        );
+    }
+
+    // The script inferred from the region decides between locales of the
+    // same region, whichever is available first.
+    for available in [["zh-Hans-TW", "zh-Hant-TW"], ["zh-Hant-TW", "zh-Hans-TW"]] {
+        let available = convert_vec_str_to_langids_lossy(available);
+        let requested = convert_vec_str_to_langids_lossy(["zh-TW"]);
+        for strategy in [
+            NegotiationStrategy::Filtering,
+            NegotiationStrategy::Matching,
+            NegotiationStrategy::Lookup,
+        ] {
+            assert_eq!(
+                negotiate_languages(&requested, &available, None, strategy),
+                &[&langid!("zh-Hant-TW")],
+                "{available:?}, {strategy:?}"
+            );
+        }
+        assert_eq!(
+            negotiate_ranked(&requested, &available),
+            &[(&langid!("zh-Hant-TW"), MatchKind::LikelySubtags)]
+        );
+    }
+
+    // The inferred script is never swapped, even in the same region.
+    let requested = convert_vec_str_to_langids_lossy(["zh-TW"]);
+    let available = convert_vec_str_to_langids_lossy(["zh-Hans-TW", "zh-Hans", "zh-Hant-HK"]);
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+        &[&langid!("zh-Hant-HK")]
+    );
+    assert_eq!(match_level(&langid!("zh-TW"), &langid!("zh-Hans-TW")), None);
+    assert_eq!(match_level(&langid!("zh-CN"), &langid!("zh-Hant")), None);
+
+    // Other Traditional Chinese regions are preferred over Simplified Chinese.
+    let requested = convert_vec_str_to_langids_lossy(["zh-HK"]);
+    let available = convert_vec_str_to_langids_lossy(["zh-Hans-CN", "zh-Hant-TW"]);
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Lookup),
+        &[&langid!("zh-Hant-TW")]
+    );
+    assert_eq!(
+        match_level(&requested[0], &available[1]),
+        Some(MatchKind::LikelySubtagsWithoutRegion)
+    );
+}
+
+#[test]
+fn negotiator_max_comparisons() {
+    let languages = ["de", "en", "es", "fr", "it", "ja", "nl", "pl", "pt", "ru"];
+    let regions = ["AT", "BR", "CA", "DE", "ES", "FR", "GB", "IT", "PL", "US"];
+    let available: Vec<LanguageIdentifier> = languages
+        .iter()
+        .flat_map(|language| {
+            regions
+                .iter()
+                .map(move |region| format!("{language}-{region}"))
+        })
+        .map(|tag| tag.parse().unwrap())
+        .collect();
+    let default = available.iter().find(|locale| **locale == langid!("en-US"));
+
+    // Followed by 676 languages which are not available.
+    let mut requested = vec![langid!("pl-PL")];
+    requested.extend(
+        (b'a'..=b'z')
+            .flat_map(|a| (b'a'..=b'z').map(move |b| [b'q', a, b]))
+            .map(|language| LanguageIdentifier::try_from_bytes(&language).unwrap()),
+    );
+    requested.push(langid!("fr-FR"));
+
+    let negotiator = Negotiator::new(NegotiationStrategy::Filtering);
+    let supported = negotiator.negotiate(&requested, &available, default);
+    assert!(supported.contains(&&langid!("fr-FR")));
+
+    // The budget runs out while trying the unavailable languages.
+    let negotiator = negotiator.with_max_comparisons(10_000);
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, default),
+        &[
+            &langid!("pl-PL"),
+            &langid!("pl-AT"),
+            &langid!("pl-BR"),
+            &langid!("pl-CA"),
+            &langid!("pl-DE"),
+            &langid!("pl-ES"),
+            &langid!("pl-FR"),
+            &langid!("pl-GB"),
+            &langid!("pl-IT"),
+            &langid!("pl-US"),
+            &langid!("en-US")
+        ]
+    );
+
+    // A budget too small for even the first step returns the default locale only.
+    let negotiator = Negotiator::new(NegotiationStrategy::Lookup).with_max_comparisons(10);
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, default),
+        &[&langid!("en-US")]
+    );
+}
+
+#[test]
+fn langid_as_parts() {
+    assert_eq!(as_parts(&langid!("en")), ("en", None, None));
+    assert_eq!(as_parts(&langid!("en-US")), ("en", None, Some("US")));
+    assert_eq!(as_parts(&langid!("zh-Hant")), ("zh", Some("Hant"), None));
+    assert_eq!(
+        as_parts(&langid!("de-Latn-DE-1901")),
+        ("de", Some("Latn"), Some("DE"))
+    );
+    assert_eq!(as_parts(&langid!("es-419")), ("es", None, Some("419")));
+    assert_eq!(as_parts(&LanguageIdentifier::UND), ("und", None, None));
+    assert_eq!(
+        as_parts(&locale!("sr-latn-rs-u-ca-gregory").id),
+        ("sr", Some("Latn"), Some("RS"))
+    );
+
+    for tag in ["en", "sr-Latn", "de-AT", "zh-Hant-TW", "es-419"] {
+        let langid: LanguageIdentifier = tag.parse().unwrap();
+        let (language, script, region) = as_parts(&langid);
+        assert_eq!(
+            from_parts(language, script, region, &[]),
+            Ok(langid.clone())
+        );
+    }
+}
+
+#[test]
+fn negotiate_undetermined_language() {
+    assert!(matches_range("pl-PL", "und"));
+    assert!(matches_range("sr-Cyrl-RS", "und-Cyrl"));
+    assert!(!matches_range("sr-Latn-RS", "und-Cyrl"));
+    assert!(matches_range("und", "und"));
+    assert!(!matches_range("und", "pl"));
+
+    assert_eq!(
+        match_level(&langid!("de-CH"), &langid!("und-CH")),
+        Some(MatchKind::AvailableRange)
+    );
+    assert_eq!(
+        match_level(&langid!("de-CH"), &langid!("und-AT")),
+        Some(MatchKind::RegionRange)
+    );
+    assert_eq!(match_level(&langid!("sr-Cyrl"), &langid!("und-Latn")), None);
+    assert_eq!(
+        match_level(&langid!("und"), &langid!("und")),
+        Some(MatchKind::Exact)
+    );
+    assert_eq!(match_level(&langid!("und"), &langid!("de")), None);
+    assert_eq!(match_level(&langid!("und-CH"), &langid!("de-CH")), None);
+
+    let requested = convert_vec_str_to_langids_lossy(["und", "de-CH"]);
+    let available = convert_vec_str_to_langids_lossy(["de", "und-AT", "und-CH", "und"]);
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+        &[
+            &langid!("und"),
+            &langid!("de"),
+            &langid!("und-CH"),
+            &langid!("und-AT")
+        ]
+    );
+}
+
+#[test]
+fn langid_reductions() {
+    let cases = [
+        ("en-Latn-US", "en", "en-Latn", "en-US"),
+        ("en-US", "en", "en", "en-US"),
+        ("zh-Hant", "zh", "zh-Hant", "zh"),
+        ("de-DE-1901-1996", "de", "de", "de-DE"),
+        ("sr-Cyrl-RS-ekavsk", "sr", "sr-Cyrl", "sr-RS"),
+        ("und-419", "und", "und", "und-419"),
+        ("fr", "fr", "fr", "fr"),
+    ];
+    for (tag, language, language_script, language_region) in cases {
+        let langid: LanguageIdentifier = tag.parse().unwrap();
+        assert_eq!(language_only(&langid).to_string(), language, "{tag}");
+        assert_eq!(
+            language_and_script(&langid).to_string(),
+            language_script,
+            "{tag}"
+        );
+        assert_eq!(
+            language_and_region(&langid).to_string(),
+            language_region,
+            "{tag}"
+        );
+    }
+
+    // The reductions compose.
+    let langid = langid!("en-Latn-US-posix");
+    assert_eq!(
+        language_only(&language_and_region(&langid)),
+        language_only(&langid)
+    );
+    assert_eq!(
+        language_and_script(&language_and_region(&langid)),
+        langid!("en")
+    );
+    assert_eq!(language_only(&locale!("pt-BR-u-nu-latn").id), langid!("pt"));
+}
+
+#[test]
+fn negotiate_single_locale() {
+    let available = convert_vec_str_to_langids_lossy(["en-US", "fr-CA", "fr", "de-DE"]);
+    let default = Some(&available[0]);
+
+    for strategy in [
+        NegotiationStrategy::Filtering,
+        NegotiationStrategy::Matching,
+        NegotiationStrategy::Lookup,
+    ] {
+        for requested in [
+            &["fr-FR", "de"][..],
+            &["de-AT", "fr"],
+            &["pl", "en-GB"],
+            &["pl"],
+            &[],
+        ] {
+            let requested = convert_vec_str_to_langids_lossy(requested);
+            assert_eq!(
+                negotiate_one(&requested, &available, default, strategy),
+                negotiate_languages(&requested, &available, default, strategy)
+                    .first()
+                    .copied(),
+                "{requested:?} with {strategy:?}"
+            );
+            assert_eq!(
+                negotiate_one(&requested, &available, None, strategy),
+                negotiate_languages(&requested, &available, None, strategy)
+                    .first()
+                    .copied(),
+                "{requested:?} with {strategy:?}"
+            );
+        }
+    }
+
+    let requested = convert_vec_str_to_langids_lossy(["fr-FR", "de"]);
+    assert_eq!(
+        negotiate_one(
+            &requested,
+            &available,
+            default,
+            NegotiationStrategy::Filtering
+        ),
+        Some(&langid!("fr"))
+    );
+    assert_eq!(
+        negotiate_one(
+            &[langid!("pl")],
+            &available,
+            default,
+            NegotiationStrategy::Lookup
+        ),
+        Some(&langid!("en-US"))
+    );
+    assert_eq!(
+        negotiate_one(
+            &[langid!("pl")],
+            &available,
+            None,
+            NegotiationStrategy::Lookup
+        ),
+        None
+    );
+
+    // The default locale is placed according to the negotiator.
+    let negotiator = Negotiator::new(NegotiationStrategy::Filtering)
+        .with_default_behavior(DefaultBehavior::AppendAlways)
+        .with_default_position(DefaultPosition::First);
+    assert_eq!(
+        negotiator.negotiate_one(&requested, &available, default),
+        Some(&langid!("en-US"))
+    );
+}
+
+#[test]
+fn negotiator_excluded_locales() {
+    let requested = convert_vec_str_to_langids_lossy(["de-CH", "fr"]);
+    let available = convert_vec_str_to_langids_lossy(["de-CH", "de-DE", "fr", "en-US"]);
+    let default = available.last();
+
+    let negotiator =
+        Negotiator::new(NegotiationStrategy::Filtering).with_excluded_locales(&[langid!("de-CH")]);
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, default),
+        &[&langid!("de-DE"), &langid!("fr"), &langid!("en-US")]
+    );
+
+    // Excluding every match leaves the default locale, which is never excluded.
+    let negotiator = Negotiator::new(NegotiationStrategy::Lookup).with_excluded_locales(&[
+        langid!("de-CH"),
+        langid!("de-DE"),
+        langid!("fr"),
+        langid!("en-US"),
+    ]);
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, default),
+        &[&langid!("en-US")]
+    );
+
+    // Locales are excluded for every request, even when matched ones are not consumed.
+    let negotiator = Negotiator::new(NegotiationStrategy::Filtering)
+        .with_consume_matched(false)
+        .with_excluded_locales(&[langid!("de-DE")]);
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, None),
+        &[&langid!("de-CH"), &langid!("fr")]
+    );
+}
+
+#[cfg(feature = "env")]
+#[test]
+fn requested_from_env() {
+    use fluent_langneg::env::{parse_posix_locale, requested_from};
+
+    let env = |vars: &'static [(&'static str, &'static str)]| {
+        move |name: &str| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    };
+
+    assert_eq!(parse_posix_locale("pt_BR"), Some(langid!("pt-BR")));
+    assert_eq!(
+        parse_posix_locale("ca_ES.UTF-8@valencia"),
+        Some(langid!("ca-ES"))
+    );
+    assert_eq!(
+        parse_posix_locale("sr_RS@latin"),
+        Some(langid!("sr-Latn-RS"))
+    );
+    assert_eq!(parse_posix_locale("POSIX"), None);
+    assert_eq!(parse_posix_locale(""), None);
+
+    // LC_ALL takes precedence over LC_MESSAGES, which takes precedence over LANG.
+    assert_eq!(
+        requested_from(env(&[
+            ("LANG", "fr_FR.UTF-8"),
+            ("LC_MESSAGES", "de_DE.UTF-8"),
+            ("LC_ALL", "it_IT.UTF-8"),
+        ])),
+        &[langid!("it-IT")]
+    );
+    assert_eq!(
+        requested_from(env(&[
+            ("LANG", "fr_FR.UTF-8"),
+            ("LC_MESSAGES", "de_DE.UTF-8"),
+            ("LC_ALL", ""),
+        ])),
+        &[langid!("de-DE")]
+    );
+
+    // LANGUAGE comes first, and is ignored for the C locale.
+    assert_eq!(
+        requested_from(env(&[
+            ("LANG", "pl_PL.UTF-8"),
+            ("LANGUAGE", "en_GB:en::pl_PL"),
+        ])),
+        &[langid!("en-GB"), langid!("en"), langid!("pl-PL")]
+    );
+    assert_eq!(
+        requested_from(env(&[("LANG", "C.UTF-8"), ("LANGUAGE", "en_GB:en")])),
+        &[]
+    );
+    assert_eq!(requested_from(env(&[("LANGUAGE", "en_GB:en")])), &[]);
+
+    let available = convert_vec_str_to_langids_lossy(["en-US", "pl", "de"]);
+    assert_eq!(
+        negotiate_languages(
+            &requested_from(env(&[("LC_ALL", "de_AT.UTF-8"), ("LANGUAGE", "cs:pl")])),
+            &available,
+            None,
+            NegotiationStrategy::Filtering
+        ),
+        &[&langid!("pl"), &langid!("de")]
+    );
+}
+
+#[test]
+fn negotiator_script_fallback() {
+    let requested = convert_vec_str_to_langids_lossy(["mn-Mong-CN", "en"]);
+    let available = convert_vec_str_to_langids_lossy(["en-US", "mn-Cyrl", "mn-Mong"]);
+    let negotiator = Negotiator::new(NegotiationStrategy::Filtering).with_script_fallback(
+        language!("mn"),
+        script!("Mong"),
+        script!("Cyrl"),
+    );
+
+    // The requested script is preferred to its fallback.
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, None),
+        &[&langid!("mn-Mong"), &langid!("mn-Cyrl"), &langid!("en-US")]
+    );
+
+    let available = convert_vec_str_to_langids_lossy(["en-US", "mn-Cyrl-MN"]);
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, None),
+        &[&langid!("mn-Cyrl-MN"), &langid!("en-US")]
+    );
+    assert_eq!(
+        Negotiator::new(NegotiationStrategy::Filtering).negotiate(&requested, &available, None),
+        &[&langid!("en-US")]
+    );
+
+    // The fallback applies in one direction only.
+    let requested = convert_vec_str_to_langids_lossy(["mn-Cyrl"]);
+    let available = convert_vec_str_to_langids_lossy(["mn-Mong"]);
+    assert!(negotiator
+        .negotiate(&requested, &available, None)
+        .is_empty());
+}
+
+#[test]
+fn langid_builders() {
+    let base = langid!("de-1901");
+
+    let candidates: Vec<LanguageIdentifier> = ["DE", "AT", "CH"]
+        .iter()
+        .map(|region| with_region(&base, region).unwrap())
+        .collect();
+    assert_eq!(
+        candidates,
+        convert_vec_str_to_langids_lossy(["de-DE-1901", "de-AT-1901", "de-CH-1901"])
+    );
+
+    assert_eq!(
+        with_region(&base, "AT")
+            .and_then(|langid| with_script(&langid, "Latn"))
+            .and_then(|langid| with_variants(&langid, &["1996", "1901", "1996"])),
+        "de-Latn-AT-1901-1996".parse()
+    );
+
+    // Each step is validated, and an error stops the chain.
+    assert!(with_region(&base, "").is_err());
+    assert!(with_region(&base, "A").is_err());
+    assert!(with_script(&base, "Lat1").is_err());
+    assert!(with_variants(&base, &["1996", "x"]).is_err());
+    assert!(with_script(&base, "Latin")
+        .and_then(|langid| with_region(&langid, "AT"))
+        .is_err());
+
+    // The base identifier is left untouched.
+    assert_eq!(base, langid!("de-1901"));
+}
+
+#[test]
+fn negotiate_prefers_more_specific() {
+    let requested = convert_vec_str_to_locales_lossy(["en-US-u-ca-gregory"]);
+    let available = convert_vec_str_to_locales_lossy(["en", "en-US"]);
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+        &[&available[1], &available[0]]
+    );
+
+    let requested = convert_vec_str_to_langids_lossy(["sr-Latn-RS-ekavsk"]);
+    let available =
+        convert_vec_str_to_langids_lossy(["sr", "sr-RS", "sr-Latn", "sr-Latn-RS", "sr-Cyrl-RS"]);
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+        &[
+            &langid!("sr-Latn-RS"),
+            &langid!("sr-RS"),
+            &langid!("sr-Latn"),
+            &langid!("sr")
+        ]
+    );
+    // A single pick is still the first available locale of the step.
+    for strategy in [NegotiationStrategy::Matching, NegotiationStrategy::Lookup] {
+        assert_eq!(
+            negotiate_languages(&requested, &available, None, strategy),
+            &[&langid!("sr")]
+        );
+    }
+
+    let requested = convert_vec_str_to_langids_lossy(["en-Latn-US"]);
+    let available = convert_vec_str_to_langids_lossy(["en", "en-US"]);
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+        &[&langid!("en-US"), &langid!("en")]
+    );
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Lookup),
+        &[&langid!("en")]
+    );
+    assert_eq!(
+        negotiate_map(&requested, &available, NegotiationStrategy::Filtering)[0].1,
+        Some(&langid!("en-US"))
+    );
+    assert_eq!(
+        negotiate_map(&requested, &available, NegotiationStrategy::Lookup)[0].1,
+        Some(&langid!("en"))
+    );
+}
+
+/// Extended filtering from RFC4647, section 3.3.2, used as a reference for
+/// differential testing of the matcher.
+fn reference_extended_filter(tag: &str, range: &str) -> bool {
+    let tag: Vec<String> = tag.split('-').map(str::to_ascii_lowercase).collect();
+    let range: Vec<String> = range.split('-').map(str::to_ascii_lowercase).collect();
+    if range[0] != "*" && range[0] != tag[0] {
+        return false;
+    }
+
+    let (mut t, mut r) = (1, 1);
+    while r < range.len() {
+        if range[r] == "*" {
+            r += 1;
+        } else if t >= tag.len() {
+            return false;
+        } else if range[r] == tag[t] {
+            t += 1;
+            r += 1;
+        } else if tag[t].len() == 1 {
+            return false;
+        } else {
+            t += 1;
+        }
+    }
+    true
+}
+
+#[test]
+fn matches_range_differential() {
+    // Variants are listed alphabetically, since this crate sorts them while
+    // the reference compares them in order, and `posix` is left out, since
+    // it is never covered by a range.
+    fn combinations<'a>(
+        languages: &[&'a str],
+        scripts: &[&'a str],
+        regions: &[&'a str],
+    ) -> Vec<String> {
+        let variants = [
+            "",
+            "-1901",
+            "-1996",
+            "-fonipa",
+            "-1901-1996",
+            "-1996-fonipa",
+        ];
+        let mut tags = vec![];
+        for language in languages {
+            for script in scripts {
+                for region in regions {
+                    for variant in variants {
+                        tags.push(format!("{language}{script}{region}{variant}"));
+                    }
+                }
+            }
+        }
+        tags
+    }
+    let tags = combinations(
+        &["en", "de", "sr"],
+        &["", "-Latn", "-Cyrl"],
+        &["", "-US", "-DE", "-419"],
+    );
+    let ranges = combinations(
+        &["en", "de", "*"],
+        &["", "-Latn", "-*"],
+        &["", "-US", "-419", "-*"],
+    );
+    let available: Vec<&str> = tags.iter().map(String::as_str).collect();
+
+    let mut divergences = vec![];
+    for range in &ranges {
+        let filtered = filter_available(range, &available);
+        for tag in &available {
+            let expected = reference_extended_filter(tag, range);
+            if filtered.contains(tag) != expected || matches_range(tag, range) != expected {
+                divergences.push(format!("{tag} in {range}: expected {expected}"));
+            }
+        }
+    }
+    assert!(divergences.is_empty(), "{divergences:#?}");
+}
+
+#[test]
+fn negotiate_ranked_candidates() {
+    let requested = convert_vec_str_to_langids_lossy(["sr-Latn-RS", "en-GB"]);
+    let available =
+        convert_vec_str_to_langids_lossy(["en-US", "sr-Cyrl-RS", "en", "sr-Latn", "sr-RS"]);
+
+    let ranked = negotiate_ranked(&requested, &available);
+    assert_eq!(
+        ranked,
+        &[
+            (&langid!("sr-Latn"), MatchKind::AvailableRange),
+            (&langid!("sr-RS"), MatchKind::AvailableRange),
+            (&langid!("en"), MatchKind::AvailableRange),
+            (&langid!("en-US"), MatchKind::LikelySubtagsWithoutRegion),
+        ]
+    );
+
+    // The first candidate is the one Lookup returns, whatever the strategy
+    // of the negotiator, when the locales matched at the same step are
+    // equally specific.
+    for strategy in [
+        NegotiationStrategy::Filtering,
+        NegotiationStrategy::Matching,
+        NegotiationStrategy::Lookup,
+    ] {
+        let negotiator = Negotiator::new(strategy);
+        assert_eq!(negotiator.negotiate_ranked(&requested, &available), ranked);
+    }
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Lookup),
+        &[ranked[0].0]
+    );
+
+    // Other settings of the negotiator still apply.
+    let negotiator = Negotiator::new(NegotiationStrategy::Lookup)
+        .with_min_acceptable_level(MatchKind::AvailableRange);
+    assert_eq!(negotiator.negotiate_ranked(&requested, &available).len(), 3);
+    assert!(negotiate_ranked(&requested, &[] as &[LanguageIdentifier]).is_empty());
+
+    // Otherwise, they are ranked like the strategy picks them.
+    let requested = convert_vec_str_to_langids_lossy(["en-Latn-US"]);
+    let available = convert_vec_str_to_langids_lossy(["en", "en-US"]);
+    for strategy in [
+        NegotiationStrategy::Filtering,
+        NegotiationStrategy::Matching,
+        NegotiationStrategy::Lookup,
+    ] {
+        let negotiator = Negotiator::new(strategy);
+        let ranked = negotiator.negotiate_ranked(&requested, &available);
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].1, MatchKind::AvailableRange);
+        assert_eq!(
+            negotiator.negotiate(&requested, &available, None)[0],
+            ranked[0].0,
+            "{strategy:?}"
+        );
+    }
+    assert_eq!(
+        negotiate_ranked(&requested, &available)[0].0,
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Lookup)[0]
+    );
+    assert_eq!(
+        negotiate_ranked(&requested, &available)[0].0,
+        &langid!("en")
+    );
+}
+
+#[test]
+fn negotiate_numeric_regions() {
+    for (numeric, alpha) in [
+        ("en-840", "en-US"),
+        ("de-276", "de-DE"),
+        ("pt-076", "pt-BR"),
+        ("sr-Latn-688", "sr-Latn-RS"),
+        ("zh-158", "zh-TW"),
+    ] {
+        let mut langid = parse_lossy(numeric);
+        assert!(canonicalize_aliases(&mut langid), "{numeric}");
+        assert_eq!(langid, parse_lossy(alpha));
+        assert!(is_equivalent(&parse_lossy(numeric), &parse_lossy(alpha)));
+    }
+
+    // Codes of groups of countries have no alpha-2 code.
+    for tag in ["es-419", "en-001", "fr-150"] {
+        let mut langid = parse_lossy(tag);
+        assert!(!canonicalize_aliases(&mut langid), "{tag}");
+        assert_eq!(langid, parse_lossy(tag));
+    }
+
+    let available = convert_vec_str_to_langids_lossy(["en-GB", "en-US", "es-ES", "es-419"]);
+    let requested = convert_vec_str_to_langids_lossy(["en-840", "es-419"]);
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Matching),
+        &[&langid!("en-US"), &langid!("es-419")]
+    );
+    assert_eq!(
+        match_level(&langid!("en-840"), &langid!("en-US")),
+        Some(MatchKind::RegionAlias)
+    );
+}
+
+#[test]
+fn negotiate_include_unmatched() {
+    let requested = convert_vec_str_to_langids_lossy(["it", "de-AT", "pl", "fr", "cs"]);
+    let available = convert_vec_str_to_langids_lossy(["en-US", "fr", "de-DE", "de"]);
+    let default = available.first();
+
+    let negotiator = Negotiator::new(NegotiationStrategy::Filtering);
+    assert_eq!(
+        negotiator.negotiate_detailed(&requested, &available, default),
+        &[
+            Negotiated::Matched(&requested[1], &available[3]),
+            Negotiated::Matched(&requested[1], &available[2]),
+            Negotiated::Matched(&requested[3], &available[1]),
+            Negotiated::Default(&available[0]),
+        ]
+    );
+
+    // Unmatched requested locales are placed in the requested order,
+    // before a default locale appended at the end.
+    let negotiator = negotiator.with_include_unmatched(true);
+    assert_eq!(
+        negotiator.negotiate_detailed(&requested, &available, default),
+        &[
+            Negotiated::Unmatched(&requested[0]),
+            Negotiated::Matched(&requested[1], &available[3]),
+            Negotiated::Matched(&requested[1], &available[2]),
+            Negotiated::Unmatched(&requested[2]),
+            Negotiated::Matched(&requested[3], &available[1]),
+            Negotiated::Unmatched(&requested[4]),
+            Negotiated::Default(&available[0]),
+        ]
+    );
+
+    let negotiator = negotiator.with_default_position(DefaultPosition::First);
+    assert_eq!(
+        negotiator.negotiate_detailed(&requested, &available, default)[..2],
+        [
+            Negotiated::Default(&available[0]),
+            Negotiated::Unmatched(&requested[0]),
+        ]
+    );
+
+    // Without any match, every requested locale is unmatched.
+    let negotiator = Negotiator::new(NegotiationStrategy::Lookup).with_include_unmatched(true);
+    assert_eq!(
+        negotiator.negotiate_detailed(&requested[..1], &available, None),
+        &[Negotiated::Unmatched(&requested[0])]
+    );
+}
+
+#[test]
+fn canonical_locale_as_key() {
+    use std::collections::{HashMap, HashSet};
+
+    let mut resources: HashMap<CanonicalLocale, &str> = HashMap::new();
+    for (tag, path) in [
+        ("EN_us", "en-US/main.ftl"),
+        ("sr-latn-rs", "sr-Latn-RS/main.ftl"),
+        ("de-u-co-phonebk", "de-phonebk/main.ftl"),
+        ("i-klingon", "tlh/main.ftl"),
+    ] {
+        resources.insert(tag.parse().unwrap(), path);
+    }
+
+    assert_eq!(resources.get("en-US"), Some(&"en-US/main.ftl"));
+    assert_eq!(resources.get("sr-Latn-RS"), Some(&"sr-Latn-RS/main.ftl"));
+    assert_eq!(
+        resources.get("de-u-co-phonebk"),
+        Some(&"de-phonebk/main.ftl")
+    );
+    assert_eq!(resources.get("tlh"), Some(&"tlh/main.ftl"));
+    // Keys are looked up by their canonical tag only.
+    assert_eq!(resources.get("en_US"), None);
+    assert_eq!(resources.get("de"), None);
+
+    let locale = CanonicalLocale::from(locale!("fr-CA"));
+    assert_eq!(locale.as_str(), "fr-CA");
+    assert_eq!(AsRef::<str>::as_ref(&locale), "fr-CA");
+    assert_eq!(locale.to_string(), "fr-CA");
+    assert_eq!(locale.locale(), &locale!("fr-CA"));
+    assert_eq!(CanonicalLocale::from(langid!("fr-CA")), locale);
+    assert!("@#$".parse::<CanonicalLocale>().is_err());
+
+    let unique: HashSet<CanonicalLocale> = ["de-AT", "de_at", "DE-at"]
+        .iter()
+        .map(|tag| tag.parse().unwrap())
+        .collect();
+    assert_eq!(unique.len(), 1);
+
+    // It can be negotiated, and the results used for lookups.
+    let available: Vec<CanonicalLocale> = resources.keys().cloned().collect();
+    let requested = convert_vec_str_to_langids_lossy(["de-CH", "en"]);
+    let supported = negotiate_languages(&requested, &available, None, NegotiationStrategy::Lookup);
+    assert_eq!(resources[supported[0].as_str()], "de-phonebk/main.ftl");
+}
+
+#[test]
+fn negotiate_grouped_by_level() {
+    let requested = convert_vec_str_to_langids_lossy(["en-GB", "fr-CA", "pl", "es-MX"]);
+    let available =
+        convert_vec_str_to_langids_lossy(["en-US", "en-GB", "en", "fr-FR", "es-419", "es-ES"]);
+
+    let grouped = negotiate_grouped(&requested, &available, NegotiationStrategy::Filtering);
+    #[cfg(feature = "cldr")]
+    assert_eq!(
+        grouped.into_iter().collect::<Vec<_>>(),
+        vec![
+            (MatchKind::Exact, vec![&langid!("en-GB")]),
+            (MatchKind::AvailableRange, vec![&langid!("en")]),
+            (
+                MatchKind::LikelySubtagsWithoutRegion,
+                vec![&langid!("en-US"), &langid!("fr-FR"), &langid!("es-ES")]
+            ),
+            (MatchKind::RegionRange, vec![&langid!("es-419")]),
+        ]
+    );
+    // The mock data replaces the regions of `fr-CA` and `es-MX` when maximizing.
+    #[cfg(not(feature = "cldr"))]
+    assert_eq!(
+        grouped.into_iter().collect::<Vec<_>>(),
+        vec![
+            (MatchKind::Exact, vec![&langid!("en-GB")]),
+            (MatchKind::AvailableRange, vec![&langid!("en")]),
+            (
+                MatchKind::LikelySubtags,
+                vec![&langid!("fr-FR"), &langid!("es-ES")]
+            ),
+            (
+                MatchKind::LikelySubtagsWithoutRegion,
+                vec![&langid!("en-US")]
+            ),
+            (MatchKind::RegionRange, vec![&langid!("es-419")]),
+        ]
+    );
+
+    // The groups contain what the strategy negotiates.
+    let grouped = negotiate_grouped(&requested, &available, NegotiationStrategy::Lookup);
+    assert_eq!(
+        grouped.into_iter().collect::<Vec<_>>(),
+        vec![(MatchKind::Exact, vec![&langid!("en-GB")])]
+    );
+    assert!(
+        negotiate_grouped(&requested[2..3], &available, NegotiationStrategy::Matching).is_empty()
+    );
+}
+
+#[test]
+fn sign_language_tags() {
+    for (tag, preferred) in [
+        ("sgn-US", "ase"),
+        ("sgn-GB", "bfi"),
+        ("sgn-FR", "fsl"),
+        ("sgn-JP", "jsl"),
+        ("sgn-BE-FR", "sfb"),
+        ("sgn-BE-NL", "vgt"),
+        ("sgn-CH-DE", "sgg"),
+    ] {
+        assert_eq!(grandfathered(tag), Some(parse_lossy(preferred)), "{tag}");
+        assert_eq!(parse_lossy(tag), parse_lossy(preferred), "{tag}");
+
+        // Tags with a region only can be parsed without replacing them.
+        if let Ok(mut langid) = LanguageIdentifier::try_from_bytes(tag.as_bytes()) {
+            assert!(canonicalize_aliases(&mut langid), "{tag}");
+            assert_eq!(langid, parse_lossy(preferred), "{tag}");
+        }
+    }
+
+    // Sign languages without a registered tag are kept.
+    assert_eq!(grandfathered("sgn-CA"), None);
+    assert_eq!(parse_lossy("sgn-CA"), langid!("sgn-CA"));
+    assert!(!canonicalize_aliases(&mut langid!("sgn-CA")));
+
+    let requested = convert_vec_str_to_langids_lossy(["sgn-US", "en-US"]);
+    let available = convert_vec_str_to_langids_lossy(["en-US", "ase", "bfi"]);
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+        &[&langid!("ase"), &langid!("en-US")]
+    );
+}
+
+#[test]
+fn negotiator_available_weights() {
+    use std::collections::HashMap;
+
+    let weights = HashMap::from([
+        ("en-US".to_string(), 0.9),
+        ("en_GB".to_string(), 0.4),
+        ("fr".to_string(), 1.0),
+    ]);
+    let negotiator =
+        Negotiator::new(NegotiationStrategy::Filtering).with_available_weights(weights.clone());
+
+    // Same-level matches are reordered by their weights.
+    let requested = convert_vec_str_to_langids_lossy(["en-IE", "fr"]);
+    let available = convert_vec_str_to_langids_lossy(["en-AU", "en-GB", "en-US", "fr"]);
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, None),
+        &[
+            &langid!("en-US"),
+            &langid!("en-GB"),
+            &langid!("en-AU"),
+            &langid!("fr")
+        ]
+    );
+    assert_eq!(
+        Negotiator::new(NegotiationStrategy::Filtering).negotiate(&requested, &available, None),
+        &[
+            &langid!("en-US"),
+            &langid!("en-AU"),
+            &langid!("en-GB"),
+            &langid!("fr")
+        ]
+    );
+
+    // Weights don't override the requested order or earlier steps.
+    let requested = convert_vec_str_to_langids_lossy(["en-GB", "en"]);
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, None),
+        &[&langid!("en-GB"), &langid!("en-US"), &langid!("en-AU")]
+    );
+
+    let negotiator =
+        Negotiator::new(NegotiationStrategy::Lookup).with_available_weights(weights.clone());
+    let requested = convert_vec_str_to_langids_lossy(["en-IE"]);
+    let available = convert_vec_str_to_langids_lossy(["en-AU", "en-GB"]);
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, None),
+        &[&langid!("en-GB")]
+    );
+
+    // Region priorities take precedence over weights.
+    let negotiator = Negotiator::new(NegotiationStrategy::Lookup)
+        .with_available_weights(weights)
+        .with_region_priority(language!("en"), &[region!("AU")]);
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, None),
+        &[&langid!("en-AU")]
+    );
+}
+
+#[test]
+fn gettext_language_chain() {
+    assert_eq!(to_gettext_language_chain(&[]), "");
+    assert_eq!(
+        to_gettext_language_chain(&["de", "en-US", "en"]),
+        "de:en_US:en"
+    );
+    assert_eq!(
+        to_gettext_language_chain(&["pt_BR", " fr-ca "]),
+        "pt_BR:fr_CA"
+    );
+
+    // Scripts are written as modifiers when gettext has one for them.
+    assert_eq!(
+        to_gettext_language_chain(&["sr-Cyrl-RS", "sr-Latn-RS", "uz-Latn"]),
+        "sr_RS@cyrillic:sr_RS@latin:uz@latin"
+    );
+    assert_eq!(
+        to_gettext_language_chain(&["zh-Hant-TW", "zh-Hans-TW", "ca-ES-valencia"]),
+        "zh_TW:ca_ES"
+    );
+
+    // Invalid tags are skipped.
+    assert_eq!(to_gettext_language_chain(&["de-", "x", "en"]), "de:en");
+}
+
+#[cfg(feature = "env")]
+#[test]
+fn gettext_language_chain_round_trip() {
+    use fluent_langneg::env::requested_from;
+
+    // The chain is read back as the same locales.
+    let chain = to_gettext_language_chain(&["sr-Latn-RS", "de-AT", "en"]);
+    let requested = requested_from(move |name| match name {
+        "LANG" => Some("en_US.UTF-8".to_string()),
+        "LANGUAGE" => Some(chain.clone()),
+        _ => None,
+    });
+    assert_eq!(
+        requested,
+        &[
+            langid!("sr-Latn-RS"),
+            langid!("de-AT"),
+            langid!("en"),
+            langid!("en-US")
+        ]
+    );
+}
+
+#[test]
+fn negotiator_available_preference() {
+    let requested = convert_vec_str_to_langids_lossy(["es-MX", "en"]);
+    let available = convert_vec_str_to_langids_lossy(["es-AR", "en", "es-CO", "es-419"]);
+
+    // Equally preferred locales keep the available order by default.
+    for negotiator in [
+        Negotiator::new(NegotiationStrategy::Filtering),
+        Negotiator::new(NegotiationStrategy::Filtering)
+            .with_available_preference(AvailablePreference::FirstWins),
+    ] {
+        assert_eq!(
+            negotiator.negotiate(&requested, &available, None),
+            &[
+                &langid!("es-AR"),
+                &langid!("es-CO"),
+                &langid!("es-419"),
+                &langid!("en")
+            ]
+        );
+    }
+
+    let negotiator = Negotiator::new(NegotiationStrategy::Filtering)
+        .with_available_preference(AvailablePreference::LastWins);
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, None),
+        &[
+            &langid!("es-419"),
+            &langid!("es-CO"),
+            &langid!("es-AR"),
+            &langid!("en")
+        ]
+    );
+
+    for (preference, expected) in [
+        (AvailablePreference::FirstWins, langid!("es-AR")),
+        (AvailablePreference::LastWins, langid!("es-419")),
+    ] {
+        for strategy in [NegotiationStrategy::Matching, NegotiationStrategy::Lookup] {
+            let negotiator = Negotiator::new(strategy).with_available_preference(preference);
+            assert_eq!(
+                negotiator.negotiate(&requested, &available, None)[0],
+                &expected,
+                "{preference:?}, {strategy:?}"
+            );
+        }
+    }
+
+    // Other preferences take precedence over the available order.
+    let negotiator = Negotiator::new(NegotiationStrategy::Lookup)
+        .with_available_preference(AvailablePreference::LastWins)
+        .with_paradigm_locales(&[langid!("es-AR")]);
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, None),
+        &[&langid!("es-AR")]
+    );
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn negotiate_available_stream() {
+    use std::future::Future;
+    use std::pin::{pin, Pin};
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    use fluent_langneg::stream::negotiate_stream;
+
+    /// Yields its tags, returning `Pending` before each of them.
+    struct MockStream {
+        tags: Vec<&'static str>,
+        ready: bool,
+    }
+
+    impl futures_core::Stream for MockStream {
+        type Item = String;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<String>> {
+            if !self.ready {
+                self.ready = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            self.ready = false;
+            if self.tags.is_empty() {
+                return Poll::Ready(None);
+            }
+            Poll::Ready(Some(self.tags.remove(0).to_string()))
+        }
+    }
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = pin!(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    let requested = convert_vec_str_to_langids_lossy(["de-AT", "fr"]);
+    let default = langid!("en-US");
+    let manifest = || MockStream {
+        tags: vec!["en-US", "de", "de_de", "fr-CA", ".DS_Store", "de-DE"],
+        ready: false,
+    };
+
+    assert_eq!(
+        block_on(negotiate_stream(
+            &requested,
+            manifest(),
+            Some(&default),
+            NegotiationStrategy::Filtering
+        )),
+        &[
+            langid!("de"),
+            langid!("de-DE"),
+            langid!("fr-CA"),
+            langid!("en-US")
+        ]
+    );
+    assert_eq!(
+        block_on(negotiate_stream(
+            &requested,
+            manifest(),
+            None,
+            NegotiationStrategy::Lookup
+        )),
+        &[langid!("de")]
+    );
+
+    let empty = MockStream {
+        tags: vec![],
+        ready: false,
+    };
+    assert_eq!(
+        block_on(negotiate_stream(
+            &requested,
+            empty,
+            Some(&default),
+            NegotiationStrategy::Lookup
+        )),
+        &[langid!("en-US")]
+    );
+}
+
+#[test]
+fn validate_available_redundant_pairs() {
+    let available = convert_vec_str_to_langids_lossy([
+        "zh-CN",
+        "en-US",
+        "iw-IL",
+        "zh-Hans-CN",
+        "en-us",
+        "he-IL",
+        "zh-TW",
+        "en",
+        "und",
+    ]);
+    assert_eq!(
+        validate_available(&available),
+        &[
+            AvailabilityWarning::Maximized {
+                locale: &available[0],
+                maximized: &available[3],
+            },
+            AvailabilityWarning::Duplicate {
+                first: &available[1],
+                second: &available[4],
+            },
+            AvailabilityWarning::Alias {
+                first: &available[2],
+                second: &available[5],
+            },
+        ]
+    );
+
+    let available = convert_vec_str_to_langids_lossy(["en-Latn-US", "sr-Cyrl", "en"]);
+    assert_eq!(
+        validate_available(&available),
+        &[AvailabilityWarning::Maximized {
+            locale: &available[2],
+            maximized: &available[0],
+        }]
+    );
+
+    let available = convert_vec_str_to_langids_lossy(["de", "de-AT", "fr-CA", "und-Latn-US"]);
+    assert!(validate_available(&available).is_empty());
+    assert!(validate_available::<LanguageIdentifier>(&[]).is_empty());
+}
+
+#[test]
+fn negotiator_assume_language() {
+    let available = convert_vec_str_to_langids_lossy(["en", "sq", "sr-Latn", "de"]);
+    let negotiator =
+        Negotiator::new(NegotiationStrategy::Filtering).with_assume_language(language!("sq"));
+
+    // Likely subtags are not added to requested locales without a language.
+    let requested = convert_vec_str_to_langids_lossy(["und-XK", "de-AT"]);
+    assert_eq!(
+        Negotiator::new(NegotiationStrategy::Filtering).negotiate(&requested, &available, None),
+        &[&langid!("de")]
+    );
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, None),
+        &[&langid!("sq"), &langid!("de")]
+    );
+
+    // The region has no likely subtags in the test data, while the CLDR data
+    // falls back to the likely language of `und`.
+    let requested = convert_vec_str_to_langids_lossy(["und-AQ"]);
+    #[cfg(feature = "cldr")]
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, None),
+        &[&langid!("en")]
+    );
+    #[cfg(not(feature = "cldr"))]
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, None),
+        &[&langid!("sq")]
+    );
+
+    // Locales with a language are not affected.
+    let requested = convert_vec_str_to_langids_lossy(["fr-XK"]);
+    assert!(negotiator
+        .negotiate(&requested, &available, None)
+        .is_empty());
+
+    // Steps 1 and 2 still match the requested locale as it is.
+    let available = convert_vec_str_to_langids_lossy(["sq", "und-XK"]);
+    let requested = convert_vec_str_to_langids_lossy(["und-XK"]);
+    assert_eq!(
+        negotiator.negotiate(&requested, &available, None),
+        &[&langid!("und-XK"), &langid!("sq")]
+    );
+}
+
+#[test]
+fn locale_extensions() {
+    let locale: Locale = "ja-JP-a-foo-bar-t-it-u-ca-japanese-nu-jpanfin-x-private"
+        .parse()
+        .unwrap();
+    for (singleton, expected) in [
+        ('a', Some("foo-bar")),
+        ('t', Some("it")),
+        ('u', Some("ca-japanese-nu-jpanfin")),
+        ('U', Some("ca-japanese-nu-jpanfin")),
+        ('x', Some("private")),
+        ('b', None),
+        ('z', None),
+    ] {
+        assert_eq!(
+            has_extension(&locale, singleton),
+            expected.is_some(),
+            "{singleton}"
+        );
+        assert_eq!(
+            extension(&locale, singleton).as_deref(),
+            expected,
+            "{singleton}"
+        );
+    }
+
+    let locale = locale!("en-US");
+    for singleton in ['u', 't', 'x', 'a'] {
+        assert!(!has_extension(&locale, singleton));
+        assert_eq!(extension(&locale, singleton), None);
+    }
+
+    // Extensions are kept through negotiation, so they can be queried afterwards.
+    let requested = convert_vec_str_to_langids_lossy(["th"]);
+    let available = convert_vec_str_to_locales_lossy(["en-US", "th-TH-u-ca-buddhist"]);
+    let supported = negotiate_languages(&requested, &available, None, NegotiationStrategy::Lookup);
+    assert_eq!(extension(supported[0], 'u').as_deref(), Some("ca-buddhist"));
+}
+
+#[test]
+fn negotiate_result_accessors() {
+    let requested = convert_vec_str_to_langids_lossy(["de-AT", "pl", "fr-CA", "en"]);
+    let available = convert_vec_str_to_langids_lossy(["en-US", "de", "fr", "de-AT"]);
+
+    let result = negotiate_result(
+        &requested,
+        &available,
+        Some(&available[0]),
+        NegotiationStrategy::Filtering,
+    );
+    assert_eq!(
+        result.matches,
+        &[
+            MatchedLocale {
+                locale: &available[3],
+                requested: Some(0),
+                kind: Some(MatchKind::Exact),
+            },
+            MatchedLocale {
+                locale: &available[1],
+                requested: Some(0),
+                kind: Some(MatchKind::AvailableRange),
+            },
+            MatchedLocale {
+                locale: &available[2],
+                requested: Some(2),
+                kind: Some(MatchKind::AvailableRange),
+            },
+            MatchedLocale {
+                locale: &available[0],
+                requested: Some(3),
+                kind: Some(MatchKind::LikelySubtags),
+            },
+        ]
+    );
+    assert_eq!(result.best(), Some(&available[3]));
+    assert_eq!(
+        result.tags(),
+        negotiate_languages(
+            &requested,
+            &available,
+            Some(&available[0]),
+            NegotiationStrategy::Filtering
+        )
+    );
+    // The default has been matched by a requested locale.
+    assert!(!result.used_default());
+
+    let requested = convert_vec_str_to_langids_lossy(["pl"]);
+    let result = negotiate_result(
+        &requested,
+        &available,
+        Some(&available[0]),
+        NegotiationStrategy::Lookup,
+    );
+    assert_eq!(
+        result.matches,
+        &[MatchedLocale {
+            locale: &available[0],
+            requested: None,
+            kind: None,
+        }]
+    );
+    assert_eq!(result.best(), Some(&available[0]));
+    assert!(result.used_default());
+
+    let result = negotiate_result(&requested, &available, None, NegotiationStrategy::Lookup);
+    assert!(result.matches.is_empty());
+    assert_eq!(result.best(), None);
+    assert!(result.tags().is_empty());
+    assert!(!result.used_default());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn negotiate_result_serde() {
+    let requested = convert_vec_str_to_langids_lossy(["fr-CA", "en"]);
+    let available = convert_vec_str_to_langids_lossy(["en-US", "fr", "de"]);
+    let result = negotiate_result(
+        &requested,
+        &available,
+        Some(&available[0]),
+        NegotiationStrategy::Filtering,
+    );
+    assert_eq!(
+        serde_json::to_string(&result).unwrap(),
+        r#"{"supported":["fr","en-US"],"default_used":false}"#
+    );
+
+    let requested = convert_vec_str_to_langids_lossy(["pl"]);
+    let result = negotiate_result(
+        &requested,
+        &available,
+        Some(&available[0]),
+        NegotiationStrategy::Filtering,
+    );
+    assert_eq!(
+        serde_json::to_value(&result).unwrap(),
+        serde_json::json!({"supported": ["en-US"], "default_used": true})
+    );
+
+    // Locales are serialized with their extensions.
+    let available = convert_vec_str_to_locales_lossy(["th-TH-u-ca-buddhist"]);
+    let requested = convert_vec_str_to_langids_lossy(["th"]);
+    let result = negotiate_result(&requested, &available, None, NegotiationStrategy::Lookup);
+    assert_eq!(
+        serde_json::to_string(&result).unwrap(),
+        r#"{"supported":["th-TH-u-ca-buddhist"],"default_used":false}"#
+    );
+}
+
+#[test]
+fn root_locale() {
+    // `root` is parsed as the undetermined language.
+    assert_eq!(
+        convert_vec_str_to_langids(["root", "ROOT", " root "]),
+        Ok(vec![langid!("und"), langid!("und"), langid!("und")])
+    );
+    assert_eq!(convert_vec_str_to_locales(["root"]), Ok(vec![Locale::UND]));
+    assert_eq!(parse_lossy("root"), langid!("und"));
+    assert!(convert_vec_str_to_langids(["root-US"]).is_err());
+
+    // The root locale doesn't match other requested locales...
+    let available = convert_vec_str_to_langids_lossy(["root", "fr", "de-AT"]);
+    assert_eq!(match_level(&langid!("de-CH"), &available[0]), None);
+    assert_eq!(
+        match_level(&langid!("und"), &available[0]),
+        Some(MatchKind::Exact)
+    );
+
+    // ...but is used as the default locale.
+    let requested = convert_vec_str_to_langids_lossy(["de-CH", "fr"]);
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+        &[&langid!("de-AT"), &langid!("fr"), &langid!("und")]
+    );
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Lookup),
+        &[&langid!("de-AT")]
+    );
+    let requested = convert_vec_str_to_langids_lossy(["pl"]);
+    for strategy in [
+        NegotiationStrategy::Filtering,
+        NegotiationStrategy::Matching,
+        NegotiationStrategy::Lookup,
+    ] {
+        let result = negotiate_result(&requested, &available, None, strategy);
+        assert_eq!(result.tags(), &[&langid!("und")]);
+        assert!(result.used_default());
+    }
+
+    // An explicit default takes precedence.
+    assert_eq!(
+        negotiate_languages(
+            &requested,
+            &available,
+            Some(&available[1]),
+            NegotiationStrategy::Lookup
+        ),
+        &[&langid!("fr")]
+    );
+    assert!(Negotiator::new(NegotiationStrategy::Lookup)
+        .with_default_behavior(DefaultBehavior::Never)
+        .negotiate(&requested, &available, None)
+        .is_empty());
+
+    // The root locale is matched by a requested root locale.
+    let requested = convert_vec_str_to_langids_lossy(["root", "fr"]);
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering),
+        &[&langid!("und"), &langid!("fr")]
+    );
+
+    // Empty requested tags are skipped.
+    let requested = convert_vec_str_to_langids_lossy(["", "fr"]);
+    assert_eq!(
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Lookup),
+        &[&langid!("fr")]
+    );
+}
+
+#[test]
+fn available_set_round_trip() {
+    use fluent_langneg::available::AvailableSet;
+
+    let tags = [
+        "en-US",
+        "de",
+        "en_us",
+        "sr-Latn-RS",
+        "sl-rozaj-biske-1994",
+        "und",
+        "i-klingon",
+        "es-419",
+        ".DS_Store",
+    ];
+    let set = AvailableSet::precompute(&tags);
+    assert_eq!(*set, available_from_tags(tags).0);
+
+    let bytes = set.to_bytes();
+    assert_eq!(AvailableSet::from_bytes(&bytes), Ok(set.clone()));
+    assert_eq!(
+        AvailableSet::from_bytes(&AvailableSet::precompute(&[]).to_bytes()),
+        Ok(AvailableSet::default())
+    );
+
+    // The set negotiates like the parsed tags.
+    let requested = convert_vec_str_to_langids_lossy(["sl-rozaj", "en-GB", "tlh"]);
+    let available = available_from_tags(tags).0;
+    assert_eq!(
+        negotiate_languages(&requested, &set, None, NegotiationStrategy::Filtering),
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering)
+    );
+    assert_eq!(
+        Negotiator::new(NegotiationStrategy::Lookup).negotiate(&requested, &set, None),
+        &[&set[3]]
+    );
+
+    // Invalid data is rejected.
+    assert!(AvailableSet::from_bytes(&[]).is_err());
+    assert!(AvailableSet::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    let mut other_version = bytes.clone();
+    other_version[0] += 1;
+    assert!(AvailableSet::from_bytes(&other_version).is_err());
+    let mut invalid_language = bytes.clone();
+    invalid_language[1..4].copy_from_slice(b"E N");
+    assert!(AvailableSet::from_bytes(&invalid_language).is_err());
+}